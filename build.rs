@@ -0,0 +1,14 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Only the server serves gRPC.
+    #[cfg(feature = "server")]
+    {
+        // Use the vendored protoc, unless one is given.
+        if std::env::var_os("PROTOC").is_none() {
+            std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+        }
+
+        tonic_prost_build::compile_protos("proto/typerecommendation.proto")?;
+    }
+
+    Ok(())
+}
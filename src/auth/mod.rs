@@ -0,0 +1,159 @@
+mod options;
+
+use std::sync::Arc;
+
+use warp::{
+    http::{header, HeaderMap},
+    reject::Reject,
+    Filter,
+};
+
+pub use options::{AuthOptions, Role};
+
+/// Header with the API key, as an alternative to `Authorization: Bearer <key>`.
+pub const API_KEY: &str = "x-api-key";
+
+/// Rejection for requests without an API key, or with a key that is not configured.
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl Reject for Unauthorized {}
+
+/// Rejection for requests with an API key that doesn't have the role the route needs.
+#[derive(Debug)]
+pub struct Forbidden(pub Role);
+
+impl Reject for Forbidden {}
+
+/// Checks the API key of requests against the configured keys.
+///
+/// Can be cloned. All clones share the same keys.
+#[derive(Clone)]
+pub struct Auth {
+    options: Arc<AuthOptions>,
+}
+
+impl Auth {
+    pub fn new(options: AuthOptions) -> Self {
+        Auth {
+            options: Arc::new(options),
+        }
+    }
+
+    /// Returns Ok if a request with headers can use a route that needs role.
+    ///
+    /// Every request is allowed when there are no keys.
+    pub fn check(&self, headers: &HeaderMap, role: Role) -> Result<(), warp::Rejection> {
+        let key: Option<&str> = request_key(
+            headers.get(API_KEY).and_then(|value| value.to_str().ok()),
+            headers
+                .get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok()),
+        );
+
+        self.check_key(key, role)
+    }
+
+    /// Same as Auth::check, for the API key of a request, see request_key.
+    pub fn check_key(&self, key: Option<&str>, role: Role) -> Result<(), warp::Rejection> {
+        if self.options.keys.is_empty() || (role == Role::Read && self.options.public_read) {
+            return Ok(());
+        }
+
+        match key.and_then(|key| self.options.keys.get(key)) {
+            None => Err(warp::reject::custom(Unauthorized)),
+            Some(granted) if *granted < role => Err(warp::reject::custom(Forbidden(role))),
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// API key of a request, from its API_KEY header, or else its `Authorization: Bearer <key>` header.
+pub fn request_key<'a>(
+    api_key: Option<&'a str>,
+    authorization: Option<&'a str>,
+) -> Option<&'a str> {
+    match api_key {
+        Some(key) => Some(key),
+        None => authorization
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim),
+    }
+}
+
+/// Rejects requests with Unauthorized or Forbidden unless their API key has role, see Auth::check.
+pub fn require(
+    auth: Auth,
+    role: Role,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::headers_cloned()
+        .and_then(move |headers: HeaderMap| {
+            let allowed = auth.check(&headers, role);
+
+            async move { allowed }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn auth(public_read: bool) -> Auth {
+        Auth::new(AuthOptions {
+            keys: HashMap::from([
+                ("reader".to_string(), Role::Read),
+                ("voter".to_string(), Role::Vote),
+                ("admin".to_string(), Role::Admin),
+            ]),
+            public_read,
+        })
+    }
+
+    #[tokio::test]
+    async fn roles() {
+        let vote = require(auth(false), Role::Vote).map(|| "voted");
+
+        let rejection = warp::test::request().filter(&vote).await.unwrap_err();
+        assert!(rejection.find::<Unauthorized>().is_some());
+
+        let rejection = warp::test::request()
+            .header(API_KEY, "unknown")
+            .filter(&vote)
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<Unauthorized>().is_some());
+
+        let rejection = warp::test::request()
+            .header(API_KEY, "reader")
+            .filter(&vote)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            rejection.find::<Forbidden>(),
+            Some(Forbidden(Role::Vote))
+        ));
+
+        for key in ["voter", "admin"] {
+            let voted = warp::test::request()
+                .header("authorization", format!("Bearer {key}"))
+                .filter(&vote)
+                .await;
+            assert_eq!(voted.unwrap(), "voted");
+        }
+    }
+
+    #[tokio::test]
+    async fn public_read() {
+        let read = require(auth(true), Role::Read).map(|| "read");
+        assert_eq!(warp::test::request().filter(&read).await.unwrap(), "read");
+
+        let vote = require(auth(true), Role::Vote).map(|| "voted");
+        assert!(warp::test::request().filter(&vote).await.is_err());
+
+        let open = require(Auth::new(AuthOptions::default()), Role::Admin).map(|| "open");
+        assert_eq!(warp::test::request().filter(&open).await.unwrap(), "open");
+    }
+}
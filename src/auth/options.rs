@@ -0,0 +1,45 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// What an API key is allowed to do. Each role includes the ones before it.
+///
+/// - Read: Get suggestions, stats and metrics.
+/// - Vote: Vote for names.
+/// - Admin: Reload dictionaries and set metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Role {
+    Read,
+    Vote,
+    Admin,
+}
+
+impl FromStr for Role {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "read" => Ok(Self::Read),
+            "vote" => Ok(Self::Vote),
+            "admin" => Ok(Self::Admin),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Read => write!(f, "read"),
+            Self::Vote => write!(f, "vote"),
+            Self::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+/// API keys and their roles.
+#[derive(Debug, Clone, Default)]
+pub struct AuthOptions {
+    /// Role of each API key. If empty, every request is allowed.
+    pub keys: HashMap<String, Role>,
+    /// If true, routes that only need Role::Read don't need a key.
+    pub public_read: bool,
+}
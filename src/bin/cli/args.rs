@@ -0,0 +1,258 @@
+use std::fmt::Display;
+
+use typerecommendation::{procedures::load::BadRecordPolicy, tree::SuggestionNumber, ExportFormat};
+
+/// Text printed by --help.
+pub const HELP: &str = "\
+Queries and administers a data file offline, with the same Tree as the server.
+
+Usage: typerecommendation-cli <COMMAND> <DATA_PATH> [ARGS] [OPTIONS]
+
+Commands:
+  load <DATA_PATH>                  Load the data file and print how many records were loaded
+  query <DATA_PATH> <PREFIX>...     Print the top suggestions for each prefix
+  vote <DATA_PATH> <VOTES_PATH>     Vote for each name in VOTES_PATH, one per line, and print the result
+  stats <DATA_PATH>                 Print the structure of the Tree
+  export <DATA_PATH>                Print every entry, in order of name
+
+Options:
+  -n, --suggestions <N>     Maximum number of suggestions per prefix, default 10
+  --tags <TAGS>             query: Only entries with all of these comma separated tags
+  --exclude-tags <TAGS>     query: Skip entries with any of these comma separated tags
+  --bad-records <POLICY>    skip or abort on invalid records, default skip
+  --format <FORMAT>         export: json, jsonl or csv, default json
+  -o, --output <PATH>       export: Write to PATH instead of stdout
+                            vote: Export the entries to PATH after voting
+  -h, --help                Print this help, then exit
+
+The json format is the format of the data file, so it can be loaded again.
+";
+
+/// What to do with the data file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Load,
+    /// Prefixes to suggest names for.
+    Query(Vec<String>),
+    /// Path of the file with the names to vote for.
+    Vote(String),
+    Stats,
+    Export,
+}
+
+/// Command line arguments.
+#[derive(Debug)]
+pub struct Args {
+    /// None if --help was given.
+    pub command: Option<Command>,
+    pub data_path: String,
+    pub suggestions: SuggestionNumber,
+    pub bad_records: BadRecordPolicy,
+    pub tags: Option<String>,
+    pub exclude_tags: Option<String>,
+    pub format: ExportFormat,
+    pub output: Option<String>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Args {
+            command: None,
+            data_path: String::new(),
+            suggestions: 10,
+            bad_records: BadRecordPolicy::Skip,
+            tags: None,
+            exclude_tags: None,
+            format: ExportFormat::Json,
+            output: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ArgsError {
+    UnknownCommand(String),
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue(String, String),
+    MissingArgument(&'static str),
+    UnexpectedArgument(String),
+}
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCommand(command) => write!(f, "Unknown command {command} ."),
+            Self::UnknownFlag(flag) => write!(f, "Unknown flag {flag} ."),
+            Self::MissingValue(flag) => write!(f, "Flag {flag} requires a value."),
+            Self::InvalidValue(flag, value) => write!(f, "Invalid value {value} for {flag} ."),
+            Self::MissingArgument(argument) => write!(f, "Missing argument {argument} ."),
+            Self::UnexpectedArgument(argument) => write!(f, "Unexpected argument {argument} ."),
+        }
+    }
+}
+
+impl Args {
+    /// Parse args, without the program name.
+    ///
+    /// Flags take their value as the next argument or after '=', e.g. `-n 5` or `--format=csv`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, ArgsError> {
+        let mut parsed = Args::default();
+        let mut positional: Vec<String> = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            if arg == "-h" || arg == "--help" {
+                return Ok(Args::default());
+            }
+
+            if !arg.starts_with('-') {
+                positional.push(arg);
+                continue;
+            }
+
+            let (flag, value): (String, Option<String>) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.into(), Some(value.into())),
+                None => (arg, None),
+            };
+
+            let value = || -> Result<String, ArgsError> {
+                value
+                    .or_else(|| args.next())
+                    .ok_or_else(|| ArgsError::MissingValue(flag.clone()))
+            };
+
+            match flag.as_str() {
+                "-n" | "--suggestions" => {
+                    let value: String = value()?;
+                    parsed.suggestions = match value.parse() {
+                        Ok(suggestions) if suggestions > 0 => suggestions,
+                        _ => return Err(ArgsError::InvalidValue(flag, value)),
+                    };
+                }
+                "--tags" => parsed.tags = Some(value()?),
+                "--exclude-tags" => parsed.exclude_tags = Some(value()?),
+                "--bad-records" => {
+                    let value: String = value()?;
+                    parsed.bad_records = value
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue(flag, value))?;
+                }
+                "--format" => {
+                    let value: String = value()?;
+                    parsed.format = value
+                        .parse()
+                        .map_err(|_| ArgsError::InvalidValue(flag, value))?;
+                }
+                "-o" | "--output" => parsed.output = Some(value()?),
+                _ => return Err(ArgsError::UnknownFlag(flag)),
+            }
+        }
+
+        let mut positional = positional.into_iter();
+
+        let command: String = positional
+            .next()
+            .ok_or(ArgsError::MissingArgument("COMMAND"))?;
+
+        parsed.data_path = positional
+            .next()
+            .ok_or(ArgsError::MissingArgument("DATA_PATH"))?;
+
+        let command: Command = match command.as_str() {
+            "load" => Command::Load,
+            "stats" => Command::Stats,
+            "export" => Command::Export,
+            "vote" => Command::Vote(
+                positional
+                    .next()
+                    .ok_or(ArgsError::MissingArgument("VOTES_PATH"))?,
+            ),
+            "query" => {
+                let prefixes: Vec<String> = positional.by_ref().collect();
+                if prefixes.is_empty() {
+                    return Err(ArgsError::MissingArgument("PREFIX"));
+                }
+                Command::Query(prefixes)
+            }
+            _ => return Err(ArgsError::UnknownCommand(command)),
+        };
+
+        if let Some(argument) = positional.next() {
+            return Err(ArgsError::UnexpectedArgument(argument));
+        }
+
+        parsed.command = Some(command);
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn commands() {
+        let args: Args = parse(&["query", "names.json", "amy", "-n", "5", "bo"]).unwrap();
+        assert_eq!(
+            args.command,
+            Some(Command::Query(vec!["amy".into(), "bo".into()]))
+        );
+        assert_eq!(args.data_path, "names.json");
+        assert_eq!(args.suggestions, 5);
+
+        let args: Args = parse(&["vote", "names.json", "votes.txt", "-o", "out.json"]).unwrap();
+        assert_eq!(args.command, Some(Command::Vote("votes.txt".into())));
+        assert_eq!(args.output.as_deref(), Some("out.json"));
+
+        let args: Args = parse(&["export", "names.json", "--format=csv"]).unwrap();
+        assert_eq!(args.command, Some(Command::Export));
+        assert_eq!(args.format, ExportFormat::Csv);
+
+        assert_eq!(parse(&["stats", "--help"]).unwrap().command, None);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse(&[]).unwrap_err(),
+            ArgsError::MissingArgument("COMMAND")
+        );
+        assert_eq!(
+            parse(&["load"]).unwrap_err(),
+            ArgsError::MissingArgument("DATA_PATH")
+        );
+        assert_eq!(
+            parse(&["query", "names.json"]).unwrap_err(),
+            ArgsError::MissingArgument("PREFIX")
+        );
+        assert_eq!(
+            parse(&["stats", "names.json", "extra"]).unwrap_err(),
+            ArgsError::UnexpectedArgument("extra".into())
+        );
+        assert_eq!(
+            parse(&["serve", "names.json"]).unwrap_err(),
+            ArgsError::UnknownCommand("serve".into())
+        );
+        assert_eq!(
+            parse(&["export", "names.json", "--format", "xml"]).unwrap_err(),
+            ArgsError::InvalidValue("--format".into(), "xml".into())
+        );
+        assert_eq!(
+            parse(&["query", "names.json", "a", "-n", "0"]).unwrap_err(),
+            ArgsError::InvalidValue("-n".into(), "0".into())
+        );
+        assert_eq!(
+            parse(&["load", "names.json", "--output"]).unwrap_err(),
+            ArgsError::MissingValue("--output".into())
+        );
+        assert_eq!(
+            parse(&["load", "names.json", "--port", "1"]).unwrap_err(),
+            ArgsError::UnknownFlag("--port".into())
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! Queries and administers a data file offline, without starting the server.
+//!
+//! Uses the same Tree and procedures as the server, so suggestions match the ones it would serve.
+
+mod args;
+
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+};
+
+use typerecommendation::{
+    procedures::stats::tree::Stats, tree::Counter, Entry, Export, ExportFormat, Get, LoadError,
+    LoadOptions, LoadSummary, TagFilter, Tree, Vote, VoteResult,
+};
+
+use crate::args::{Args, Command, HELP};
+
+#[derive(Debug)]
+enum CliError {
+    Load(LoadError),
+    // Path of the file and the error returned while reading or writing it
+    Io(String, std::io::Error),
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Load(err) => write!(f, "Error Loading Tree. {err}"),
+            Self::Io(path, err) => write!(f, "Error accessing {path}. {err}"),
+        }
+    }
+}
+
+/// Load the data file and run command, writing the results to out.
+async fn run(args: Args, command: Command, out: &mut impl Write) -> Result<(), CliError> {
+    let stdout = |err: std::io::Error| CliError::Io("stdout".into(), err);
+
+    let options = LoadOptions {
+        path: args.data_path,
+        bad_records: args.bad_records,
+        allow_missing: false,
+    };
+
+    let (tree, summary): (Tree, LoadSummary) = Tree::new(args.suggestions, options)
+        .await
+        .map_err(CliError::Load)?;
+
+    match command {
+        Command::Load => writeln!(out, "{summary}").map_err(stdout)?,
+        // The output of the other commands may be read by other programs, so the summary goes to stderr.
+        _ if summary.skipped > 0 => eprintln!("{summary}"),
+        _ => {}
+    }
+
+    match command {
+        Command::Load => {}
+        Command::Query(prefixes) => {
+            let filter = TagFilter::parse(args.tags.as_deref(), args.exclude_tags.as_deref());
+
+            for prefix in &prefixes {
+                if prefixes.len() > 1 {
+                    writeln!(out, "{prefix}:").map_err(stdout)?;
+                }
+
+                // A prefix that isn't in the Tree has no suggestions.
+                let top: Vec<Entry> = tree.get_top_filtered(prefix, &filter).unwrap_or_default();
+
+                for entry in top {
+                    writeln!(out, "{}\t{}", entry.get_name(), entry.get_times()).map_err(stdout)?;
+                }
+            }
+        }
+        Command::Vote(path) => {
+            let file = File::open(&path).map_err(|err| CliError::Io(path.clone(), err))?;
+
+            let mut applied: Counter = 0;
+            let mut not_found: Vec<String> = Vec::new();
+
+            for line in BufReader::new(file).lines() {
+                let line: String = line.map_err(|err| CliError::Io(path.clone(), err))?;
+                let name: &str = line.trim();
+
+                if name.is_empty() || name.starts_with('#') {
+                    continue;
+                }
+
+                match tree.vote(name) {
+                    VoteResult::Success(_) => applied += 1,
+                    VoteResult::NotFound => not_found.push(name.into()),
+                }
+            }
+
+            for name in &not_found {
+                eprintln!("Name {name} not found.");
+            }
+
+            writeln!(
+                out,
+                "Applied {applied} votes. {} names not found.",
+                not_found.len()
+            )
+            .map_err(stdout)?;
+
+            if let Some(output) = args.output {
+                let written: Counter = export(&tree, args.format, &output)?;
+                writeln!(out, "Exported {written} entries to {output} .").map_err(stdout)?;
+            }
+        }
+        Command::Stats => writeln!(out, "{}", tree.stats()).map_err(stdout)?,
+        Command::Export => match args.output {
+            None => {
+                tree.export(args.format, &mut *out).map_err(stdout)?;
+            }
+            Some(output) => {
+                let written: Counter = export(&tree, args.format, &output)?;
+                writeln!(out, "Exported {written} entries to {output} .").map_err(stdout)?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Export every entry of tree to the file at path.
+fn export(tree: &Tree, format: ExportFormat, path: &str) -> Result<Counter, CliError> {
+    let file = File::create(path).map_err(|err| CliError::Io(path.into(), err))?;
+
+    tree.export(format, BufWriter::new(file))
+        .map_err(|err| CliError::Io(path.into(), err))
+}
+
+#[tokio::main]
+async fn main() {
+    let mut args = match Args::parse(std::env::args().skip(1)) {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("{err}\n\n{HELP}");
+            std::process::exit(2);
+        }
+    };
+
+    let Some(command) = args.command.take() else {
+        print!("{HELP}");
+        return;
+    };
+
+    let mut out = std::io::stdout().lock();
+
+    if let Err(err) = run(args, command, &mut out).await {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::*;
+
+    fn temp_file(dir: &Path, name: &str, contents: &str) -> String {
+        let path: PathBuf = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    fn data_file(dir: &Path) -> String {
+        temp_file(
+            dir,
+            "names.json",
+            r#"{ "Amy": 10, "Amelia": 30, "Amanda": { "times": 20, "tags": ["sales"] }, "Bob": 5 }"#,
+        )
+    }
+
+    async fn cli(args: &[&str]) -> Result<String, CliError> {
+        let mut args: Args = Args::parse(args.iter().map(|arg| arg.to_string())).unwrap();
+        let command: Command = args.command.take().unwrap();
+
+        let mut out: Vec<u8> = Vec::new();
+        run(args, command, &mut out).await?;
+
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[tokio::test]
+    async fn query() {
+        let dir = tempfile::tempdir().unwrap();
+        let data: String = data_file(dir.path());
+
+        let out: String = cli(&["query", &data, "am", "-n", "2"]).await.unwrap();
+        assert_eq!(out, "Amelia\t30\nAmanda\t20\n");
+
+        let out: String = cli(&["query", &data, "amy", "Bo", "carl"]).await.unwrap();
+        assert_eq!(out, "amy:\nAmy\t10\nBo:\nBob\t5\ncarl:\n");
+
+        let out: String = cli(&["query", &data, "am", "--exclude-tags", "sales"])
+            .await
+            .unwrap();
+        assert_eq!(out, "Amelia\t30\nAmy\t10\n");
+
+        let out: String = cli(&["load", &data]).await.unwrap();
+        assert_eq!(
+            out,
+            format!("Loaded 4 entries from {data}. Skipped 0 invalid records.\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn vote_and_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let data: String = data_file(dir.path());
+        let votes: String = temp_file(
+            dir.path(),
+            "votes.txt",
+            "# Votes of the day\nbob\n\n  Bob  \nCarl\n",
+        );
+        let output: String = dir.path().join("voted.json").to_string_lossy().into_owned();
+
+        let out: String = cli(&["vote", &data, &votes, "-o", &output]).await.unwrap();
+        assert_eq!(
+            out,
+            format!("Applied 2 votes. 1 names not found.\nExported 4 entries to {output} .\n")
+        );
+
+        // The exported file is a data file
+        let out: String = cli(&["export", &output, "--format", "csv"]).await.unwrap();
+        assert_eq!(
+            out,
+            "name,times,tags,metadata\nAmanda,20,sales,\nAmelia,30,,\nAmy,10,,\nBob,7,,\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing: String = dir
+            .path()
+            .join("missing.json")
+            .to_string_lossy()
+            .into_owned();
+
+        assert!(matches!(
+            cli(&["stats", &missing]).await,
+            Err(CliError::Load(LoadError::Io(..)))
+        ));
+
+        let data: String = data_file(dir.path());
+        assert!(matches!(
+            cli(&["vote", &data, &missing]).await,
+            Err(CliError::Io(path, _)) if path == missing
+        ));
+
+        let out: String = cli(&["stats", &data]).await.unwrap();
+        assert!(out.starts_with("15 nodes, 4 names, max depth 6,"));
+    }
+}
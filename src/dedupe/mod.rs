@@ -0,0 +1,175 @@
+mod options;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::Arc,
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+pub use options::DedupeOptions;
+
+/// Who sent a vote: the client address, and the user in the request if there is one.
+///
+/// The user only tells apart voters behind the same address. Changing it doesn't make a vote new
+/// to another address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Voter {
+    pub addr: IpAddr,
+    pub user: Option<String>,
+}
+
+impl Voter {
+    pub fn new(addr: IpAddr, user: Option<&str>) -> Self {
+        Voter {
+            addr,
+            user: user.map(Into::into),
+        }
+    }
+}
+
+/// Client and name of a vote, with the name in lowercase.
+type Key = (Voter, String);
+
+struct Votes {
+    // Time of the counted vote for each key.
+    seen: HashMap<Key, Instant>,
+    // Keys in the order they were counted. The window is the same for every key, so this is also expiry order.
+    order: VecDeque<(Key, Instant)>,
+}
+
+/// Recently counted votes, to acknowledge repeated votes without counting them again.
+///
+/// Votes are forgotten once they are older than the window, or when more than max_entries are remembered.
+///
+/// Can be cloned. All clones share the same votes.
+#[derive(Clone)]
+pub struct Dedupe {
+    options: DedupeOptions,
+    votes: Arc<Mutex<Votes>>,
+}
+
+impl Dedupe {
+    pub fn new(options: DedupeOptions) -> Self {
+        let votes = Votes {
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        };
+
+        Dedupe {
+            options,
+            votes: Arc::new(Mutex::new(votes)),
+        }
+    }
+
+    /// Returns true if voter already voted for name within the window.
+    /// Otherwise remembers this vote and returns false.
+    pub fn is_duplicate(&self, voter: &Voter, name: &str) -> bool {
+        self.is_duplicate_at(voter, name, Instant::now())
+    }
+
+    fn is_duplicate_at(&self, voter: &Voter, name: &str, now: Instant) -> bool {
+        let DedupeOptions {
+            window,
+            max_entries,
+        } = self.options;
+
+        if window.is_zero() || max_entries == 0 {
+            return false;
+        }
+
+        let mut votes = self.votes.lock();
+        let Votes { seen, order } = &mut *votes;
+
+        while let Some((_, time)) = order.front() {
+            if now.saturating_duration_since(*time) < window {
+                break;
+            }
+
+            forget_front(seen, order);
+        }
+
+        let key: Key = (voter.clone(), name.to_ascii_lowercase());
+
+        if seen.contains_key(&key) {
+            return true;
+        }
+
+        while order.len() >= max_entries {
+            forget_front(seen, order);
+        }
+
+        seen.insert(key.clone(), now);
+        order.push_back((key, now));
+
+        false
+    }
+}
+
+/// Forget the oldest vote in order.
+fn forget_front(seen: &mut HashMap<Key, Instant>, order: &mut VecDeque<(Key, Instant)>) {
+    // A key is only counted again once it is forgotten, so it is in order at most once.
+    if let Some((key, _)) = order.pop_front() {
+        seen.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    #[test]
+    fn repeated_votes_within_window() {
+        let dedupe = Dedupe::new(DedupeOptions {
+            window: Duration::from_secs(10),
+            max_entries: 2,
+        });
+        let addr: IpAddr = "203.0.113.7".parse().unwrap();
+        let user = Voter::new(addr, Some("u-42"));
+        let addr = Voter::new(addr, None);
+        let start = Instant::now();
+
+        assert!(!dedupe.is_duplicate_at(&user, "Ann", start));
+        assert!(dedupe.is_duplicate_at(&user, "ann", start + Duration::from_secs(9)));
+        assert!(!dedupe.is_duplicate_at(&addr, "Ann", start));
+
+        // The first vote is over 10 seconds old.
+        let later: Instant = start + Duration::from_secs(10);
+        assert!(!dedupe.is_duplicate_at(&user, "Ann", later));
+        assert!(dedupe.is_duplicate_at(&user, "Ann", later));
+
+        // Only two votes are remembered, the oldest one is forgotten first.
+        assert!(!dedupe.is_duplicate_at(&user, "Bob", later));
+        assert!(!dedupe.is_duplicate_at(&addr, "Ann", later));
+        assert!(!dedupe.is_duplicate_at(&user, "Ann", later));
+        assert!(dedupe.is_duplicate_at(&addr, "Ann", later));
+    }
+
+    #[test]
+    fn user_from_another_address() {
+        let dedupe = Dedupe::new(DedupeOptions {
+            window: Duration::from_secs(10),
+            max_entries: 10,
+        });
+        let first = Voter::new("203.0.113.7".parse().unwrap(), Some("u-42"));
+        let second = Voter::new("203.0.113.8".parse().unwrap(), Some("u-42"));
+        let now = Instant::now();
+
+        assert!(!dedupe.is_duplicate_at(&first, "Ann", now));
+        assert!(!dedupe.is_duplicate_at(&second, "Ann", now));
+        assert!(dedupe.is_duplicate_at(&second, "Ann", now));
+    }
+
+    #[test]
+    fn disabled() {
+        let dedupe = Dedupe::new(DedupeOptions::default());
+        let user = Voter::new("203.0.113.7".parse().unwrap(), Some("u-42"));
+
+        assert!(!dedupe.is_duplicate(&user, "Ann"));
+        assert!(!dedupe.is_duplicate(&user, "Ann"));
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+/// Settings of the duplicate vote window.
+#[derive(Debug, Clone, Copy)]
+pub struct DedupeOptions {
+    /// Votes from the same client for the same name within this time are only counted once.
+    /// Zero counts every vote.
+    pub window: Duration,
+    /// Votes remembered at once. The oldest vote is forgotten first.
+    pub max_entries: usize,
+}
+
+impl Default for DedupeOptions {
+    fn default() -> Self {
+        DedupeOptions {
+            window: Duration::ZERO,
+            max_entries: 100_000,
+        }
+    }
+}
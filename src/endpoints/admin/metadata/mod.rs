@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use warp::{http::StatusCode, reply::Response, Filter, Reply};
+
+use crate::{
+    endpoints::rec::dictionary::dictionary_not_found,
+    entry::{Entry, Metadata},
+    procedures::metadata::{tree::SetMetadata, MetadataResult},
+    registry::{Dictionary, Registry},
+};
+
+/// Body of the PUT request.
+///
+/// - metadata: JSON object that replaces the current metadata of the name. null removes it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Input {
+    pub name: String,
+    pub metadata: Option<Metadata>,
+}
+
+/// This will be deserialized into a response for the user.
+///
+/// Output::Updated(entry) body will be the entry with its new metadata as JSON.
+/// Output::NotFound body will be a JSON object with the error message.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Output {
+    Updated(Entry),
+    NotFound { error: String },
+}
+
+/// Configure the path to require a json body, and deny a large body.
+pub fn metadata_json() -> impl Filter<Extract = (Input,), Error = warp::Rejection> + Clone {
+    warp::body::content_length_limit(1024 * 64).and(warp::body::json())
+}
+
+/// Replace the metadata of a name.
+///
+/// Metadata set this way is kept until the next reload, which takes it from the data file again.
+pub async fn set_metadata(
+    request: Input,
+    dictionary: Dictionary,
+) -> Result<Response, warp::Rejection> {
+    tracing::Span::current().record("name", request.name.as_str());
+
+    let (output, status): (Output, StatusCode) = match dictionary
+        .current()
+        .set_metadata(&request.name, request.metadata)
+    {
+        MetadataResult::Success(entry) => (Output::Updated(entry), StatusCode::OK),
+        MetadataResult::NotFound => {
+            let error: String = format!("Name {} not found", request.name);
+            (Output::NotFound { error }, StatusCode::NOT_FOUND)
+        }
+    };
+
+    let mut response: Response = warp::reply::json(&output).into_response();
+    *response.status_mut() = status;
+
+    Ok(response)
+}
+
+/// Same as set_metadata, for the dictionary with given name.
+pub async fn set_dictionary_metadata(
+    dictionary: String,
+    request: Input,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&dictionary) {
+        None => Ok(dictionary_not_found(&dictionary)),
+        Some(dictionary) => set_metadata(request, dictionary.clone()).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::{json, Value};
+
+    use crate::{
+        procedures::{find::tree::Find, load::tree::Load},
+        tree::Tree,
+    };
+
+    async fn new_dictionary() -> Dictionary {
+        let tree: Tree = Tree::new_empty(5).await;
+        tree.include(Entry::new("Ann".into(), 10)).unwrap();
+
+        Dictionary::for_tests(tree)
+    }
+
+    async fn body(response: Response) -> Value {
+        let bytes = warp::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn set_and_remove_metadata() {
+        let dictionary: Dictionary = new_dictionary().await;
+
+        let request: Input = serde_json::from_value(json!({
+            "name": "ann",
+            "metadata": { "id": 7 }
+        }))
+        .unwrap();
+
+        let response: Response = set_metadata(request, dictionary.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            body(response).await,
+            json!({ "name": "Ann", "times": 10, "metadata": { "id": 7 } })
+        );
+
+        let entry: Entry = dictionary.current().find("Ann").unwrap();
+        assert_eq!(entry.get_metadata().unwrap()["id"], 7);
+
+        let request: Input =
+            serde_json::from_value(json!({ "name": "Ann", "metadata": null })).unwrap();
+
+        let response: Response = set_metadata(request, dictionary.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(dictionary
+            .current()
+            .find("Ann")
+            .unwrap()
+            .get_metadata()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn metadata_of_unknown_name() {
+        let dictionary: Dictionary = new_dictionary().await;
+
+        for name in ["An", "Bob"] {
+            let request = Input {
+                name: name.into(),
+                metadata: Some(Metadata::new()),
+            };
+
+            let response: Response = set_metadata(request, dictionary.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use serde::Serialize;
+use warp::{http::StatusCode, reply::Response, Reply};
+
+use crate::{
+    endpoints::rec::dictionary::dictionary_not_found,
+    registry::Registry,
+    reload::{ReloadError, ReloadSummary, Reloader},
+};
+
+/// This will be deserialized into a response for the user.
+///
+/// Output::Reloaded(summary) body will be the summary as JSON.
+/// Output::Failed body will be a JSON object with the error message.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Output {
+    Reloaded(ReloadSummary),
+    Failed { error: String },
+}
+
+/// Rebuild the Tree from the data file and swap it with the current one.
+pub async fn reload(reloader: Reloader) -> Result<Response, warp::Rejection> {
+    tracing::info!(source = reloader.source(), "Reloading.");
+
+    let (output, status): (Output, StatusCode) = match reloader.reload().await {
+        Ok(summary) => {
+            tracing::info!("{summary}");
+            (Output::Reloaded(summary), StatusCode::OK)
+        }
+        Err(err) => {
+            tracing::error!("{err}");
+
+            let status = match err {
+                ReloadError::InProgress => StatusCode::CONFLICT,
+                ReloadError::Load(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+
+            let error: String = err.to_string();
+            (Output::Failed { error }, status)
+        }
+    };
+
+    let mut response: Response = warp::reply::json(&output).into_response();
+    *response.status_mut() = status;
+
+    Ok(response)
+}
+
+/// Same as reload, for the dictionary with given name.
+pub async fn reload_dictionary(
+    dictionary: String,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&dictionary) {
+        None => Ok(dictionary_not_found(&dictionary)),
+        Some(dictionary) => reload(dictionary.reloader.clone()).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    use crate::{
+        entry::Entry,
+        metrics::{ENTRIES, NODES},
+        procedures::{
+            get::tree::Get,
+            load::{tree::Load, LoadOptions},
+            vote::tree::Vote,
+        },
+        tree::Tree,
+    };
+
+    fn write_names(path: &std::path::Path, json: &str) {
+        let mut file = std::fs::File::create(path).expect("Failed to create test file");
+        file.write_all(json.as_bytes())
+            .expect("Failed to write test file");
+    }
+
+    async fn new_reloader(path: &std::path::Path, keep_votes: bool) -> Reloader {
+        let options = LoadOptions {
+            path: path.to_string_lossy().into(),
+            ..LoadOptions::default()
+        };
+
+        let tree: Tree = Tree::new_empty(5).await;
+        tree.load(&options).expect("Failed to load test file");
+
+        Reloader::new(tree, 5, options, keep_votes)
+    }
+
+    fn times(tree: &Tree, name: &str) -> u64 {
+        let values: Vec<Entry> = tree.get_top(name).expect("Name not found");
+
+        *values.first().expect("Name not found").get_times()
+    }
+
+    #[tokio::test]
+    async fn reload_swaps_tree_and_keeps_votes() {
+        let dir = tempfile::tempdir().expect("Failed to create test directory");
+        let path = dir.path().join("names.json");
+        write_names(&path, r#"{ "Ann": 10, "Bob": 5 }"#);
+
+        let reloader: Reloader = new_reloader(&path, true).await.with_gauges("reload-keep");
+        let old: Tree = reloader.current();
+        old.vote("Ann");
+        old.vote("Bob");
+
+        write_names(&path, r#"{ "Ann": 20, "Carl": 1 }"#);
+
+        let response: Response = reload(reloader.clone()).await.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let new: Tree = reloader.current();
+        assert_eq!(times(&new, "Ann"), 21);
+        assert_eq!(times(&new, "Carl"), 1);
+        assert!(new.get_top("Bob").is_err());
+
+        // Requests holding the old Tree keep using it
+        assert_eq!(times(&old, "Ann"), 11);
+
+        // "", a, an, ann, c, ca, car, carl
+        assert_eq!(ENTRIES.with_label_values(&["reload-keep"]).get(), 2);
+        assert_eq!(NODES.with_label_values(&["reload-keep"]).get(), 8);
+    }
+
+    #[tokio::test]
+    async fn reload_failure_keeps_tree() {
+        let dir = tempfile::tempdir().expect("Failed to create test directory");
+        let path = dir.path().join("names.json");
+        write_names(&path, r#"{ "Ann": 10 }"#);
+
+        let reloader: Reloader = new_reloader(&path, false).await;
+
+        write_names(&path, r#"{ "Ann": 10, "#);
+
+        let response: Response = reload(reloader.clone()).await.unwrap().into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(times(&reloader.current(), "Ann"), 10);
+    }
+}
@@ -0,0 +1,402 @@
+use std::convert::Infallible;
+
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use warp::{
+    http::{header, HeaderValue, StatusCode},
+    hyper::Body,
+    reply::Response,
+    Reply,
+};
+
+use crate::{
+    endpoints::{rec::dictionary::dictionary_not_found, NotReady},
+    procedures::export::{tree::Export, Entries, ExportFormat, ExportWriter},
+    registry::{Dictionary, Registry},
+    tree::Tree,
+};
+
+/// Bytes of exported entries sent to the client at a time.
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// Query parameters of the export endpoints.
+///
+/// - format: json, jsonl or csv. Takes precedence over the Accept header.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ExportQuery {
+    pub format: Option<String>,
+}
+
+/// Body of the responses for an unknown or unacceptable format.
+#[derive(Serialize)]
+struct FormatError {
+    error: String,
+}
+
+fn format_error(status: StatusCode, error: String) -> Response {
+    let mut response: Response = warp::reply::json(&FormatError { error }).into_response();
+    *response.status_mut() = status;
+
+    response
+}
+
+/// Formats in the order they are chosen when the Accept header doesn't prefer one.
+const FORMATS: [ExportFormat; 3] = [ExportFormat::Json, ExportFormat::Jsonl, ExportFormat::Csv];
+
+/// How specifically media_range names format: 2 for one of its media types,
+/// 1 for its top-level type like `text/*`, and 0 for `*/*`. None if it doesn't match.
+fn specificity(media_range: &str, format: ExportFormat) -> Option<u8> {
+    let media_types: &[&str] = match format {
+        ExportFormat::Json => &["application/json"],
+        ExportFormat::Jsonl => &[
+            "application/jsonl",
+            "application/x-ndjson",
+            "application/x-jsonlines",
+        ],
+        ExportFormat::Csv => &["text/csv"],
+    };
+
+    if media_types.contains(&media_range) {
+        Some(2)
+    } else if media_range == "*/*" {
+        Some(0)
+    } else {
+        media_range
+            .strip_suffix('*')
+            .filter(|top_level| top_level.ends_with('/'))
+            .filter(|top_level| format.content_type().starts_with(top_level))
+            .map(|_| 1)
+    }
+}
+
+/// The format in the Accept header with the highest q-value that can be exported, json if there is
+/// no Accept header. Formats with the same q-value are chosen in the order they are listed.
+///
+/// Each format gets the q-value of the most specific media range matching it,
+/// so `application/json;q=0, */*` refuses json but accepts the others.
+/// None if none of the accepted formats can be exported, or they all have q=0.
+fn accepted_format(accept: Option<&str>) -> Option<ExportFormat> {
+    let Some(accept) = accept else {
+        return Some(ExportFormat::Json);
+    };
+
+    let media_ranges: Vec<(String, f32)> = accept
+        .split(',')
+        .map(|media_range| {
+            let mut params = media_range.split(';');
+            let media_type: String = params
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase();
+
+            // Media ranges without a valid q-value are fully accepted.
+            let weight: f32 = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+                .filter(|weight| (0.0..=1.0).contains(weight))
+                .unwrap_or(1.0);
+
+            (media_type, weight)
+        })
+        .collect();
+
+    // The weight of each format and the position of the media range it comes from.
+    let mut best: Option<(ExportFormat, f32, usize)> = None;
+
+    for format in FORMATS {
+        let mut matched: Option<(u8, f32, usize)> = None;
+
+        for (position, (media_range, weight)) in media_ranges.iter().enumerate() {
+            if let Some(specificity) = specificity(media_range, format) {
+                if matched.is_none_or(|(most, _, _)| specificity > most) {
+                    matched = Some((specificity, *weight, position));
+                }
+            }
+        }
+
+        let Some((_, weight, position)) = matched else {
+            continue;
+        };
+
+        let better = best
+            .is_none_or(|(_, best, listed)| weight > best || (weight == best && position < listed));
+
+        if weight > 0.0 && better {
+            best = Some((format, weight, position));
+        }
+    }
+
+    best.map(|(format, _, _)| format)
+}
+
+/// Every Entry of a Tree in a format, written a chunk at a time as the client receives them.
+struct Chunks {
+    // Entries only holds weak pointers, so the Tree is kept until the export ends.
+    _tree: Tree,
+    entries: Entries,
+    format: ExportFormat,
+    // None once every Entry is written.
+    writer: Option<ExportWriter<Vec<u8>>>,
+}
+
+impl Chunks {
+    fn new(tree: Tree, format: ExportFormat) -> Self {
+        let writer = ExportWriter::new(format, Vec::with_capacity(CHUNK_BYTES))
+            .expect("Writing to memory doesn't fail.");
+
+        Chunks {
+            entries: tree.entries(),
+            _tree: tree,
+            format,
+            writer: Some(writer),
+        }
+    }
+
+    /// Write entries until there are CHUNK_BYTES of output, or the last of them.
+    /// None after the last chunk.
+    fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        let writer: &mut ExportWriter<Vec<u8>> = self.writer.as_mut()?;
+
+        for entry in self.entries.by_ref() {
+            writer
+                .write(&entry)
+                .expect("Writing to memory doesn't fail.");
+
+            if writer.get_mut().len() >= CHUNK_BYTES {
+                let chunk: Vec<u8> =
+                    std::mem::replace(writer.get_mut(), Vec::with_capacity(CHUNK_BYTES));
+
+                return Some(chunk);
+            }
+        }
+
+        let writer: ExportWriter<Vec<u8>> = self.writer.take()?;
+        let written = writer.written();
+        let last: Vec<u8> = writer.finish().expect("Writing to memory doesn't fail.");
+
+        tracing::debug!("Exported {written} entries as {}.", self.format);
+
+        Some(last)
+    }
+}
+
+impl Drop for Chunks {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            tracing::debug!("Export stopped, the client disconnected.");
+        }
+    }
+}
+
+/// GET `/export`. Every Entry of the current Tree of the dictionary, in order of name.
+///
+/// The format is json, jsonl or csv, chosen by the format query parameter or else the Accept header.
+/// The Tree is read one Node at a time, only as fast as the client receives the output, in chunks.
+pub async fn export(
+    accept: Option<String>,
+    query: ExportQuery,
+    dictionary: Dictionary,
+) -> Result<Response, warp::Rejection> {
+    let format: ExportFormat = match query.format {
+        Some(format) => match format.parse() {
+            Ok(value) => value,
+            Err(()) => {
+                let error: String = format!("Unknown format {format}, expected json, jsonl or csv");
+                return Ok(format_error(StatusCode::BAD_REQUEST, error));
+            }
+        },
+        None => match accepted_format(accept.as_deref()) {
+            Some(value) => value,
+            None => {
+                let error: String =
+                    "Exports are application/json, application/jsonl or text/csv".to_string();
+                return Ok(format_error(StatusCode::NOT_ACCEPTABLE, error));
+            }
+        },
+    };
+
+    // An empty Tree would look like an empty export.
+    if !dictionary.reloader.is_ready() {
+        return Err(warp::reject::custom(NotReady));
+    }
+
+    let chunks = Chunks::new(dictionary.current(), format);
+
+    let chunks = stream::unfold(chunks, |mut chunks| async move {
+        let chunk: Vec<u8> = chunks.next_chunk()?;
+        Some((Ok::<Vec<u8>, Infallible>(chunk), chunks))
+    });
+
+    let mut response: Response = Response::new(Body::wrap_stream(chunks));
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(format.content_type()),
+    );
+
+    Ok(response)
+}
+
+/// GET `/export/{dictionary}`.
+pub async fn dictionary_export(
+    name: String,
+    accept: Option<String>,
+    query: ExportQuery,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => export(accept, query, dictionary.clone()).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use warp::hyper::body;
+
+    use super::*;
+
+    use crate::{entry::Entry, procedures::load::tree::Load};
+
+    async fn dictionary(names: usize) -> Dictionary {
+        let tree: Tree = Tree::new_empty(10).await;
+
+        for i in 0..names {
+            tree.include(Entry::new(format!("name{i:06}"), i as u64))
+                .unwrap();
+        }
+
+        Dictionary::for_tests(tree)
+    }
+
+    async fn body(response: Response) -> String {
+        let bytes = body::to_bytes(response.into_body()).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn accept() {
+        assert_eq!(accepted_format(None), Some(ExportFormat::Json));
+        assert_eq!(
+            accepted_format(Some("text/html, text/csv;q=0.9, */*;q=0.8")),
+            Some(ExportFormat::Csv)
+        );
+        assert_eq!(
+            accepted_format(Some("application/x-ndjson")),
+            Some(ExportFormat::Jsonl)
+        );
+        assert_eq!(accepted_format(Some("application/xml")), None);
+
+        // Weights win over order, and q=0 refuses a format.
+        assert_eq!(
+            accepted_format(Some("text/csv;q=0, application/json")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(
+            accepted_format(Some("text/csv;q=0.1, application/json;q=0.9")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(
+            accepted_format(Some("application/json;q=0.5, application/jsonl")),
+            Some(ExportFormat::Jsonl)
+        );
+        assert_eq!(accepted_format(Some("text/csv; q=0")), None);
+
+        // A refused format stays refused when a wildcard also matches it.
+        assert_eq!(
+            accepted_format(Some("application/json;q=0, */*")),
+            Some(ExportFormat::Jsonl)
+        );
+        assert_eq!(
+            accepted_format(Some("text/*;q=0.5, text/csv;q=0, application/*;q=0.2")),
+            Some(ExportFormat::Json)
+        );
+        assert_eq!(accepted_format(Some("*/*")), Some(ExportFormat::Json));
+        assert_eq!(accepted_format(Some("text/*")), Some(ExportFormat::Csv));
+    }
+
+    #[tokio::test]
+    async fn formats() {
+        let dictionary: Dictionary = dictionary(3).await;
+
+        let query = ExportQuery {
+            format: Some("csv".into()),
+        };
+        let response: Response = export(Some("application/json".into()), query, dictionary.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv");
+        assert_eq!(
+            body(response).await,
+            "name,times,tags,metadata\nname000001,1,,\nname000002,2,,\n"
+        );
+
+        let response: Response = export(
+            Some("application/jsonl".into()),
+            ExportQuery::default(),
+            dictionary.clone(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "application/jsonl"
+        );
+        assert_eq!(
+            body(response).await,
+            "{\"name\":\"name000001\",\"times\":1}\n{\"name\":\"name000002\",\"times\":2}\n"
+        );
+
+        let query = ExportQuery {
+            format: Some("xml".into()),
+        };
+        let response: Response = export(None, query, dictionary.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response: Response =
+            export(Some("image/png".into()), ExportQuery::default(), dictionary)
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+
+        let loading = Dictionary::loading_for_tests().await;
+        let rejection = export(None, ExportQuery::default(), loading)
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<NotReady>().is_some());
+    }
+
+    #[tokio::test]
+    async fn streams_every_entry_in_chunks() {
+        let names: usize = 20_000;
+        let dictionary: Dictionary = dictionary(names).await;
+
+        let response: Response = export(None, ExportQuery::default(), dictionary)
+            .await
+            .unwrap();
+
+        let mut body: Body = response.into_body();
+        let mut chunks: usize = 0;
+        let mut exported: Vec<u8> = Vec::new();
+
+        while let Some(chunk) = warp::hyper::body::HttpBody::data(&mut body).await {
+            let chunk = chunk.unwrap();
+            assert!(chunk.len() < 2 * CHUNK_BYTES);
+
+            chunks += 1;
+            exported.extend_from_slice(&chunk);
+        }
+
+        assert!(chunks > 1, "Expected several chunks, got {chunks}.");
+
+        // "name000000" has times 0, so it isn't a name.
+        let parsed: HashMap<String, u64> = serde_json::from_slice(&exported).unwrap();
+        assert_eq!(parsed.len(), names - 1);
+        assert_eq!(parsed["name019999"], 19_999);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use warp::{http::StatusCode, path::FullPath, reject::Reject, reply::Response, Filter, Reply};
+
+use crate::{endpoints::rec::is_rec_path, registry::Registry};
+
+/// State of a dictionary, as reported by /readyz.
+///
+/// - Loading: The data file is being loaded for the first time, requests can't be served.
+/// - Reloading: The data file is being loaded again, requests are served by the previous Tree.
+/// - Ready: Requests are served and no reload is running.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Loading,
+    Reloading,
+    Ready,
+}
+
+/// Body of the /readyz response.
+#[derive(Serialize)]
+struct Readiness {
+    ready: bool,
+    dictionaries: BTreeMap<String, State>,
+}
+
+/// Rejection for requests that arrive before every dictionary is loaded.
+#[derive(Debug)]
+pub struct NotReady;
+
+impl Reject for NotReady {}
+
+/// GET `/healthz`. The process is alive and answering requests.
+pub async fn healthz() -> Result<warp::reply::Json, warp::Rejection> {
+    Ok(warp::reply::json(&serde_json::json!({ "status": "ok" })))
+}
+
+/// GET `/readyz`. 200 if every dictionary is loaded and not reloading, 503 otherwise.
+///
+/// The body lists the state of each dictionary.
+/// This server keeps no snapshots on disk, so there is no snapshot writer to check.
+pub async fn readyz(registry: Registry) -> Result<Response, warp::Rejection> {
+    let dictionaries: BTreeMap<String, State> = registry
+        .iter()
+        .map(|(name, dictionary)| {
+            let state: State = match (
+                dictionary.reloader.is_ready(),
+                dictionary.reloader.is_reloading(),
+            ) {
+                (false, _) => State::Loading,
+                (true, true) => State::Reloading,
+                (true, false) => State::Ready,
+            };
+
+            (name.clone(), state)
+        })
+        .collect();
+
+    let ready: bool = dictionaries.values().all(|state| *state == State::Ready);
+
+    let mut response: Response = warp::reply::json(&Readiness {
+        ready,
+        dictionaries,
+    })
+    .into_response();
+
+    if !ready {
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    }
+
+    Ok(response)
+}
+
+/// Rejects requests to `/rec` with NotReady until every dictionary has loaded once.
+///
+/// Other paths pass through, so /healthz, /readyz and /admin keep working while loading.
+pub fn when_ready(
+    registry: Registry,
+) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::path::full()
+        .and_then(move |full: FullPath| {
+            let is_rec: bool = is_rec_path(full.as_str());
+            let loading: bool = registry
+                .iter()
+                .any(|(_, dictionary)| !dictionary.reloader.is_ready());
+
+            async move {
+                match is_rec && loading {
+                    true => Err(warp::reject::custom(NotReady)),
+                    false => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::registry::Dictionary;
+
+    async fn pending_registry() -> Registry {
+        Registry::for_tests(Dictionary::loading_for_tests().await)
+    }
+
+    #[tokio::test]
+    async fn not_ready_while_loading() {
+        let registry: Registry = pending_registry().await;
+
+        let response: Response = readyz(registry.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let filter = when_ready(registry.clone()).map(|| "served");
+
+        let rejection = warp::test::request()
+            .path("/rec/am")
+            .filter(&filter)
+            .await
+            .unwrap_err();
+        assert!(rejection.find::<NotReady>().is_some());
+
+        let other = warp::test::request().path("/healthz").filter(&filter).await;
+        assert_eq!(other.unwrap(), "served");
+
+        // Loads ./names.json
+        registry.get_default().reloader.reload().await.unwrap();
+
+        let response: Response = readyz(registry.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let served = warp::test::request().path("/rec/am").filter(&filter).await;
+        assert_eq!(served.unwrap(), "served");
+    }
+}
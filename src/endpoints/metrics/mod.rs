@@ -0,0 +1,24 @@
+use prometheus::{Encoder, TextEncoder};
+use warp::{
+    http::{header::CONTENT_TYPE, StatusCode},
+    reply::Response,
+    Reply,
+};
+
+/// GET `/metrics`. All metrics in the Prometheus text format.
+///
+/// The entry and node gauges are set when each dictionary is loaded, so collecting doesn't visit the Trees.
+pub async fn metrics() -> Result<Response, warp::Rejection> {
+    let encoder = TextEncoder::new();
+    let mut body: Vec<u8> = Vec::new();
+
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut body) {
+        tracing::error!("Failed to encode metrics. {err}");
+
+        return Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+    }
+
+    let reply = warp::reply::with_header(body, CONTENT_TYPE, encoder.format_type());
+
+    Ok(reply.into_response())
+}
@@ -1,4 +1,21 @@
+pub mod admin;
+pub mod export;
+pub mod health;
+pub mod metrics;
 pub mod rec;
+pub mod stats;
 
-pub use rec::post::{vote, vote_json};
-pub use rec::prefix::get::{get_entries, get_top_entries};
+pub use admin::metadata::{metadata_json, set_dictionary_metadata, set_metadata};
+pub use admin::reload::{reload, reload_dictionary};
+pub use export::{dictionary_export, export, ExportQuery};
+pub use health::{healthz, readyz, when_ready, NotReady};
+pub use metrics::metrics;
+pub use rec::dictionary::{
+    dictionary_top, get_dictionary_entries, get_dictionary_top_entries, vote_dictionary,
+};
+pub use rec::events::{accepts_events, dictionary_events, events, EventsQuery};
+pub use rec::post::{limited_vote_json, vote};
+pub use rec::prefix::get::{get_entries, get_top_entries, Query};
+pub use rec::rec_path;
+pub use rec::session::{dictionary_session, session};
+pub use stats::{dictionary_stats, stats};
@@ -0,0 +1,159 @@
+use std::net::IpAddr;
+
+use serde::Serialize;
+use warp::{http::StatusCode, path::FullPath, reply::Response, Filter, Reply};
+
+use crate::{
+    endpoints::{get_entries, get_top_entries, rec::post::Input, vote, Query},
+    registry::{Dictionary, Registry},
+};
+
+/// Body of the response when the dictionary doesn't exist.
+#[derive(Serialize)]
+struct NotFound {
+    error: String,
+}
+
+/// 404 response with a JSON error for a dictionary that is not registered.
+pub fn dictionary_not_found(name: &str) -> Response {
+    let body = NotFound {
+        error: format!("Dictionary {name} not found"),
+    };
+
+    let mut response: Response = warp::reply::json(&body).into_response();
+    *response.status_mut() = StatusCode::NOT_FOUND;
+
+    response
+}
+
+/// Matches `/rec/{dictionary}/` with a trailing slash, if the dictionary exists, and extracts it.
+///
+/// `/rec/{prefix}` without a trailing slash, or naming something that is not a dictionary,
+/// is rejected so it falls through to the prefix search on the default dictionary.
+pub fn dictionary_top(
+    registry: Registry,
+) -> impl Filter<Extract = (Dictionary,), Error = warp::Rejection> + Clone {
+    warp::path!("rec" / String)
+        .and(warp::path::full())
+        .and_then(move |name: String, full: FullPath| {
+            let dictionary: Option<Dictionary> = match full.as_str().ends_with('/') {
+                false => None,
+                true => registry.get(&name).cloned(),
+            };
+
+            async move { dictionary.ok_or_else(warp::reject::not_found) }
+        })
+}
+
+/// GET `/rec/{dictionary}/{prefix}`.
+pub async fn get_dictionary_entries(
+    name: String,
+    prefix: String,
+    query: Query,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => {
+            let reply = get_entries(prefix, query, dictionary.clone()).await?;
+            Ok(reply.into_response())
+        }
+    }
+}
+
+/// GET `/rec/{dictionary}/`.
+pub async fn get_dictionary_top_entries(
+    dictionary: Dictionary,
+    query: Query,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    get_top_entries(query, dictionary).await
+}
+
+/// POST `/rec/{dictionary}`.
+pub async fn vote_dictionary(
+    name: String,
+    request: Input,
+    client: Option<IpAddr>,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => vote(request, client, dictionary.clone()).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use crate::{procedures::load::tree::Load, tree::Tree};
+
+    async fn new_registry() -> Registry {
+        let mut dictionaries: HashMap<String, Dictionary> = HashMap::new();
+
+        for (name, entry) in [("people", "Ann"), ("cities", "Amsterdam")] {
+            let tree: Tree = Tree::new_empty(5).await;
+            tree.include((entry, 10).into()).unwrap();
+
+            dictionaries.insert(name.into(), Dictionary::for_tests(tree));
+        }
+
+        Registry::new(dictionaries, "people".into())
+    }
+
+    #[tokio::test]
+    async fn get_from_named_dictionary() {
+        let registry: Registry = new_registry().await;
+
+        let query = Query::default();
+        let response = get_dictionary_entries(
+            "cities".into(),
+            "am".into(),
+            query.clone(),
+            registry.clone(),
+        )
+        .await
+        .unwrap();
+        let body = warp::hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap();
+
+        assert_eq!(body, r#"[{"name":"Amsterdam","times":10}]"#);
+
+        let response = get_dictionary_entries("planets".into(), "am".into(), query, registry)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn top_requires_trailing_slash_and_dictionary() {
+        let registry: Registry = new_registry().await;
+        let filter = dictionary_top(registry);
+
+        assert!(
+            warp::test::request()
+                .path("/rec/cities/")
+                .matches(&filter)
+                .await
+        );
+
+        // Without the trailing slash, "cities" is a prefix in the default dictionary.
+        assert!(
+            !warp::test::request()
+                .path("/rec/cities")
+                .matches(&filter)
+                .await
+        );
+
+        assert!(
+            !warp::test::request()
+                .path("/rec/planets/")
+                .matches(&filter)
+                .await
+        );
+    }
+}
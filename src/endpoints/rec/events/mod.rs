@@ -0,0 +1,434 @@
+use std::{collections::VecDeque, convert::Infallible};
+
+use futures_util::{stream, Stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    watch,
+};
+use warp::{http::header, reply::Response, sse::Event, Filter, Reply};
+
+use crate::{
+    endpoints::rec::dictionary::dictionary_not_found,
+    entry::Entry,
+    metrics::EVENT_STREAMS,
+    procedures::get::{tree::Get, TagFilter},
+    registry::{Dictionary, Registry},
+    tree::{Tree, TreeId},
+};
+
+/// Query parameters of the events endpoints.
+///
+/// - prefix: Only votes for names starting with it are sent, and top events are for its top list.
+///   If not set, every vote is sent, and top events are for the overall top list.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct EventsQuery {
+    pub prefix: Option<String>,
+}
+
+/// Data of a top event.
+#[derive(Debug, Serialize)]
+struct Top<'a> {
+    prefix: &'a str,
+    suggestions: &'a [Entry],
+}
+
+/// Matches requests that accept `text/event-stream`, like those of a browser EventSource.
+///
+/// Other requests to the events paths fall through to the prefix search.
+pub fn accepts_events() -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>(header::ACCEPT.as_str())
+        .and_then(|accept: Option<String>| async move {
+            match accept.is_some_and(|accept| accept.contains("text/event-stream")) {
+                true => Ok(()),
+                false => Err(warp::reject::not_found()),
+            }
+        })
+        .untuple_one()
+}
+
+/// A client of the events endpoints.
+struct Subscription {
+    dictionary: Dictionary,
+    feed: broadcast::Receiver<Entry>,
+    // Changes when the server shuts down, which ends the stream.
+    stopping: watch::Receiver<()>,
+    prefix: String,
+    // Top list last sent, and the Tree it was computed from.
+    top: Vec<Entry>,
+    root: TreeId,
+    pending: VecDeque<Event>,
+}
+
+impl Subscription {
+    fn new(dictionary: Dictionary, prefix: String, stopping: watch::Receiver<()>) -> Self {
+        let tree: Tree = dictionary.current();
+
+        // Subscribing before computing the top list, so no vote falls in between.
+        let feed = tree.subscribe();
+        let top: Vec<Entry> = top(&tree, &prefix);
+
+        let mut subscription = Subscription {
+            dictionary,
+            feed,
+            stopping,
+            prefix,
+            top,
+            root: tree.id(),
+            pending: VecDeque::new(),
+        };
+
+        // Clients start from the current top list.
+        let event: Event = subscription.top_event();
+        subscription.pending.push_back(event);
+
+        EVENT_STREAMS.inc();
+
+        subscription
+    }
+
+    fn top_event(&self) -> Event {
+        let data = Top {
+            prefix: &self.prefix,
+            suggestions: &self.top,
+        };
+
+        Event::default()
+            .event("top")
+            .json_data(data)
+            .expect("Top lists can be serialized.")
+    }
+
+    /// Queue a vote event for entry, and a top event if the vote changed the top list.
+    fn voted(&mut self, entry: Entry) {
+        let name: &[u8] = entry.get_name().as_bytes();
+        let prefix: &[u8] = self.prefix.as_bytes();
+
+        if name.len() < prefix.len() || !name[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            return;
+        }
+
+        let event = Event::default()
+            .event("vote")
+            .json_data(&entry)
+            .expect("Entries can be serialized.");
+        self.pending.push_back(event);
+
+        let tree: Tree = self.dictionary.current();
+
+        // A reloaded Tree can have other names and counts, only then is the top list searched again.
+        if self.root != tree.id() {
+            self.refresh(&tree);
+            return;
+        }
+
+        if update_top(&mut self.top, entry, &self.prefix, tree.get_suggestions()) {
+            let event: Event = self.top_event();
+            self.pending.push_back(event);
+        }
+    }
+
+    /// Queue a top event if the top list in tree is not the one last sent.
+    ///
+    /// Searches tree, only for a reloaded Tree or after missing votes.
+    fn refresh(&mut self, tree: &Tree) {
+        let top: Vec<Entry> = top(tree, &self.prefix);
+        self.root = tree.id();
+
+        if top != self.top {
+            self.top = top;
+
+            let event: Event = self.top_event();
+            self.pending.push_back(event);
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        EVENT_STREAMS.dec();
+    }
+}
+
+/// Top list for prefix in tree, empty if the prefix is not found.
+fn top(tree: &Tree, prefix: &str) -> Vec<Entry> {
+    tree.get_top_filtered(prefix, &TagFilter::default())
+        .unwrap_or_default()
+}
+
+/// Apply a vote for entry to top, the top list for prefix, without searching the Tree.
+/// Returns false if top is unchanged.
+///
+/// Like in the Tree, the name equal to the prefix comes first and the others follow by count.
+/// Votes only increase counts, so entry moves up if it is listed, and otherwise
+/// only enters a full top list by ranking above its last entry, which it replaces.
+fn update_top(top: &mut Vec<Entry>, entry: Entry, prefix: &str, suggestions: usize) -> bool {
+    let exact = |listed: &Entry| listed.get_name().eq_ignore_ascii_case(prefix);
+
+    if exact(&entry) {
+        match top.first_mut() {
+            Some(first) if exact(first) => *first = entry,
+            _ => {
+                top.insert(0, entry);
+                top.truncate(suggestions);
+            }
+        }
+
+        return true;
+    }
+
+    // The entries after the exact match are ranked.
+    let ranked: usize = usize::from(top.first().is_some_and(exact));
+
+    let listed: Option<&mut Entry> = top[ranked..]
+        .iter_mut()
+        .find(|listed| listed.get_name().eq_ignore_ascii_case(entry.get_name()));
+
+    if let Some(listed) = listed {
+        *listed = entry;
+    } else if top.len() < suggestions {
+        top.push(entry);
+    } else if top.len() > ranked && top.last().is_some_and(|last| entry > *last) {
+        *top.last_mut().expect("A full top list has a last entry.") = entry;
+    } else {
+        return false;
+    }
+
+    // Highest first, like the Tree sorts them.
+    top[ranked..].sort_by(|a, b| b.cmp(a));
+
+    true
+}
+
+/// Events of subscription, until the client leaves or the server shuts down.
+fn event_stream(subscription: Subscription) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(subscription, |mut subscription| async move {
+        loop {
+            if let Some(event) = subscription.pending.pop_front() {
+                return Some((Ok(event), subscription));
+            }
+
+            tokio::select! {
+                received = subscription.feed.recv() => match received {
+                    Ok(entry) => subscription.voted(entry),
+                    Err(RecvError::Lagged(missed)) => {
+                        // The votes are gone, but the top list can still be caught up.
+                        tracing::debug!(missed, "Events client fell behind.");
+                        let tree: Tree = subscription.dictionary.current();
+                        subscription.refresh(&tree);
+                    }
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = subscription.stopping.changed() => return None,
+            }
+        }
+    })
+}
+
+/// GET `/rec/events`: a Server-Sent Events stream of the votes applied to dictionary.
+///
+/// Sends a `vote` event with the Entry of each vote for a name starting with the prefix in query,
+/// and a `top` event with the top list for the prefix when the stream starts and each time it changes.
+pub async fn events(
+    query: EventsQuery,
+    dictionary: Dictionary,
+    stopping: watch::Receiver<()>,
+) -> Result<Response, warp::Rejection> {
+    let prefix: String = query.prefix.unwrap_or_default();
+    let subscription = Subscription::new(dictionary, prefix, stopping);
+
+    let stream = warp::sse::keep_alive().stream(event_stream(subscription));
+
+    Ok(warp::sse::reply(stream).into_response())
+}
+
+/// GET `/rec/{dictionary}/events`.
+pub async fn dictionary_events(
+    name: String,
+    query: EventsQuery,
+    registry: Registry,
+    stopping: watch::Receiver<()>,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => events(query, dictionary.clone(), stopping).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures_util::StreamExt;
+
+    use crate::{
+        procedures::{load::LoadOptions, vote::tree::Vote},
+        reload::Reloader,
+        sample::sample_tree,
+    };
+
+    async fn new_dictionary() -> Dictionary {
+        Dictionary::for_tests(sample_tree(2).await)
+    }
+
+    async fn next<S>(stream: &mut S) -> String
+    where
+        S: Stream<Item = Result<Event, Infallible>> + Unpin,
+    {
+        stream.next().await.unwrap().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn votes_and_top_changes() {
+        let dictionary: Dictionary = new_dictionary().await;
+        let (stop, stopping) = watch::channel(());
+        let subscription = Subscription::new(dictionary.clone(), "an".into(), stopping);
+        let mut stream = Box::pin(event_stream(subscription));
+
+        let event: String = next(&mut stream).await;
+        assert!(event.starts_with("event:top\n"));
+        assert!(event.contains(
+            r#""suggestions":[{"name":"Anna","times":30},{"name":"Annabel","times":20}]"#
+        ));
+
+        // Bob doesn't start with the prefix. Ann enters the top list once it passes Annabel.
+        let tree: Tree = dictionary.current();
+        tree.vote("Bob");
+        for _ in 0..11 {
+            tree.vote("Ann");
+        }
+
+        let mut events: Vec<String> = Vec::new();
+        for _ in 0..13 {
+            events.push(next(&mut stream).await);
+        }
+
+        assert!(events[0].starts_with("event:vote\n"));
+        assert!(events[0].contains(r#"{"name":"Ann","times":11}"#));
+
+        let tops: Vec<&String> = events
+            .iter()
+            .filter(|event| event.starts_with("event:top\n"))
+            .collect();
+        // Ann passes Annabel with 20 votes, and moves up with the next.
+        assert_eq!(tops.len(), 2);
+        assert!(tops[0].contains(r#"[{"name":"Anna","times":30},{"name":"Ann","times":20}]"#));
+        assert!(tops[1].contains(r#"[{"name":"Anna","times":30},{"name":"Ann","times":21}]"#));
+
+        stop.send_replace(());
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn full_name_prefix_keeps_the_name_first() {
+        let dictionary: Dictionary = new_dictionary().await;
+        let (_stop, stopping) = watch::channel(());
+        let subscription = Subscription::new(dictionary.clone(), "ann".into(), stopping);
+        let mut stream = Box::pin(event_stream(subscription));
+
+        let event: String = next(&mut stream).await;
+        assert!(event.contains(r#"[{"name":"Ann","times":10},{"name":"Anna","times":30}]"#));
+
+        // Annabel passes Anna, but Ann stays first as the exact match.
+        let tree: Tree = dictionary.current();
+        for _ in 0..11 {
+            tree.vote("Annabel");
+        }
+
+        let mut events: Vec<String> = Vec::new();
+        for _ in 0..12 {
+            events.push(next(&mut stream).await);
+        }
+
+        let last: &String = events.last().unwrap();
+        assert!(last.starts_with("event:top\n"));
+        assert!(last.contains(r#"[{"name":"Ann","times":10},{"name":"Annabel","times":31}]"#));
+        assert_eq!(
+            top(&tree, "ann"),
+            [("Ann", 10).into(), ("Annabel", 31).into()]
+        );
+    }
+
+    #[test]
+    fn top_list_updates() {
+        let mut top: Vec<Entry> = Vec::from([("Anna", 30).into(), ("Annabel", 20).into()]);
+
+        assert!(!update_top(&mut top, ("Ann", 11).into(), "an", 2));
+
+        assert!(update_top(&mut top, ("Ann", 21).into(), "an", 2));
+        assert_eq!(top, [("Anna", 30).into(), ("Ann", 21).into()]);
+
+        assert!(update_top(&mut top, ("ann", 31).into(), "an", 2));
+        assert_eq!(top, [("Ann", 31).into(), ("Anna", 30).into()]);
+
+        assert!(update_top(&mut top, ("Annie", 1).into(), "an", 3));
+        assert_eq!(top.len(), 3);
+    }
+
+    #[test]
+    fn exact_match_stays_first() {
+        let mut top: Vec<Entry> = Vec::from([("Ann", 10).into(), ("Anna", 30).into()]);
+
+        assert!(update_top(&mut top, ("Ann", 11).into(), "ann", 2));
+        assert_eq!(top, [("Ann", 11).into(), ("Anna", 30).into()]);
+
+        assert!(!update_top(&mut top, ("Annabel", 21).into(), "ann", 2));
+
+        assert!(update_top(&mut top, ("Annabel", 31).into(), "ann", 2));
+        assert_eq!(top, [("Ann", 11).into(), ("Annabel", 31).into()]);
+
+        assert!(update_top(&mut top, ("Annabel", 32).into(), "ann", 3));
+        assert!(update_top(&mut top, ("Anna", 40).into(), "ann", 3));
+        assert_eq!(
+            top,
+            [
+                ("Ann", 11).into(),
+                ("Anna", 40).into(),
+                ("Annabel", 32).into()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reload_searches_the_top_list_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("names.json");
+        std::fs::write(&path, r#"{ "Ann": 10, "Annie": 50 }"#).unwrap();
+
+        let options = LoadOptions {
+            path: path.to_string_lossy().into(),
+            ..LoadOptions::default()
+        };
+        let dictionary = Dictionary {
+            reloader: Reloader::new(sample_tree(2).await, 2, options, false),
+            ..Dictionary::for_tests(Tree::new_empty(2).await)
+        };
+
+        let (_stop, stopping) = watch::channel(());
+        let subscription = Subscription::new(dictionary.clone(), "an".into(), stopping);
+        let mut stream = Box::pin(event_stream(subscription));
+        next(&mut stream).await;
+
+        // Subscribers of the replaced Tree receive the votes of the reloaded one.
+        dictionary.reloader.reload().await.unwrap();
+        dictionary.current().vote("Ann");
+
+        assert!(next(&mut stream).await.starts_with("event:vote\n"));
+        let event: String = next(&mut stream).await;
+        assert!(event.starts_with("event:top\n"));
+        assert!(event.contains(r#"[{"name":"Annie","times":50},{"name":"Ann","times":11}]"#));
+    }
+
+    #[tokio::test]
+    async fn accept_header() {
+        let filter = accepts_events();
+
+        assert!(
+            warp::test::request()
+                .header("accept", "text/event-stream")
+                .matches(&filter)
+                .await
+        );
+        assert!(!warp::test::request().matches(&filter).await);
+    }
+}
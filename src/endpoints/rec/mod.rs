@@ -1,2 +1,24 @@
+pub mod dictionary;
+pub mod events;
 pub mod post;
 pub mod prefix;
+pub mod session;
+
+use warp::{path::FullPath, Filter};
+
+/// Returns true if path is `/rec` or under it.
+pub fn is_rec_path(path: &str) -> bool {
+    path == "/rec" || path.starts_with("/rec/")
+}
+
+/// Matches requests to `/rec` and under it, without consuming the path.
+pub fn rec_path() -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::path::full()
+        .and_then(|full: FullPath| async move {
+            match is_rec_path(full.as_str()) {
+                true => Ok(()),
+                false => Err(warp::reject::not_found()),
+            }
+        })
+        .untuple_one()
+}
@@ -1,15 +1,38 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::Arc,
+};
+
 use serde::{Deserialize, Serialize};
-use warp::Filter;
+use warp::{
+    http::{HeaderValue, StatusCode},
+    reply::Response,
+    Filter, Reply,
+};
 
 use crate::{
-    log::log_debug,
-    procedures::vote::{tree::Vote, VoteResult},
-    tree::Tree,
+    dedupe::Voter,
+    entry::Entry,
+    limit::{RateLimited, RateLimiter},
+    metrics::{DUPLICATE_VOTES, NOT_FOUND, RATE_LIMITED, VOTE_SECONDS},
+    procedures::{
+        find::tree::Find,
+        vote::{tree::Vote, VoteResult},
+    },
+    registry::Dictionary,
+    server::client_ip,
 };
 
+/// Response header telling if the vote was counted: "true", or "false" for a repeated vote.
+pub const VOTE_APPLIED: &str = "x-vote-applied";
+
+/// Body of the POST request.
+///
+/// - user: If set, the vote is also counted in the personal votes of this user.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Input {
     pub name: String,
+    pub user: Option<String>,
 }
 
 /// Configure the path to require a json body, and deny a large body.
@@ -17,32 +40,122 @@ pub fn vote_json() -> impl Filter<Extract = (Input,), Error = warp::Rejection> +
     warp::body::content_length_limit(1024 * 16).and(warp::body::json())
 }
 
-pub async fn vote(request: Input, tree: Tree) -> Result<impl warp::Reply, warp::Rejection> {
-    log_debug("---------------------------------------------------------------------");
-    println!("Post Vote Name {}", &request.name);
+/// Like vote_json, but rejects with RateLimited when the client is over the vote rate limit.
+/// Also extracts the client address, if known.
+///
+/// trusted are the proxies whose X-Forwarded-For header is used to find the client.
+/// Requests without a client address, like in tests, share a single limit.
+pub fn limited_vote_json(
+    limiter: RateLimiter,
+    trusted: Arc<Vec<IpAddr>>,
+) -> impl Filter<Extract = (Input, Option<IpAddr>), Error = warp::Rejection> + Clone {
+    vote_json()
+        .and(client_ip(trusted))
+        .and_then(move |request: Input, client: Option<IpAddr>| {
+            let address: IpAddr = client.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+            let limited = limiter.check(address, &request.name);
 
-    let response: VoteResult = tree.vote(&request.name);
-
-    response.into()
+            async move {
+                match limited {
+                    Ok(()) => Ok((request, client)),
+                    Err(wait) => {
+                        tracing::debug!(client = %address, "Vote rate limited.");
+                        RATE_LIMITED.inc();
+                        Err(warp::reject::custom(RateLimited(wait)))
+                    }
+                }
+            }
+        })
+        .untuple_one()
 }
 
-#[cfg(test)]
-mod tests {
-    use std::sync::{Arc, Weak};
+/// POST `/rec`. Counts a vote for the name in request, from client.
+///
+/// A repeated vote from the same client address and user, if there is one, within the dedupe window
+/// is answered with 200 and the current Entry, without counting it.
+/// The X-Vote-Applied header tells if the vote was counted.
+pub async fn vote(
+    request: Input,
+    client: Option<IpAddr>,
+    dictionary: Dictionary,
+) -> Result<Response, warp::Rejection> {
+    tracing::Span::current().record("name", request.name.as_str());
 
-    use parking_lot::RwLock;
-    use warp::http::StatusCode;
+    let voter: Option<Voter> = client.map(|address| Voter::new(address, request.user.as_deref()));
 
-    use crate::{entry::Entry, node::Node, procedures::vote::VoteResult};
+    if let Some(voter) = voter {
+        if dictionary.dedupe.is_duplicate(&voter, &request.name) {
+            DUPLICATE_VOTES.inc();
+            return duplicate(dictionary.current().find(&request.name));
+        }
+    }
 
-    fn new_empty_next() -> (Arc<RwLock<Node>>, VoteResult) {
-        let node: Arc<RwLock<Node>> = Node::new(None, "".into(), 0, 0);
-        let lock: Weak<RwLock<Node>> = Arc::downgrade(&node);
-        let next: VoteResult = VoteResult::Next(lock);
+    let _timer = VOTE_SECONDS.start_timer();
+    let response: VoteResult = dictionary.current().vote(&request.name);
 
-        (node, next)
+    if let VoteResult::NotFound = response {
+        NOT_FOUND.with_label_values(&["vote"]).inc();
     }
 
+    if let (VoteResult::Success(entry), Some(user)) = (&response, &request.user) {
+        dictionary.overlay.record(user, entry.get_name());
+    }
+
+    let applied: &str = match response {
+        VoteResult::Success(_) => "true",
+        _ => "false",
+    };
+    let mut response: Response = vote_response(response);
+    response
+        .headers_mut()
+        .insert(VOTE_APPLIED, HeaderValue::from_static(applied));
+
+    Ok(response)
+}
+
+/// This will be deserialized into a response for the user.
+///
+/// Output::BadRequest will be an empty body.
+/// Output::Created(entry) body will be entry deserialized as JSON.
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+enum Output {
+    BadRequest,
+    Created(Entry),
+}
+
+/// Response for the result of a vote: 201 with the Entry, or 400 if the name is not in the Tree.
+fn vote_response(result: VoteResult) -> Response {
+    let (output, status): (Output, StatusCode) = match result {
+        VoteResult::NotFound => (Output::BadRequest, StatusCode::BAD_REQUEST),
+        VoteResult::Success(entry) => (Output::Created(entry), StatusCode::CREATED),
+    };
+
+    warp::reply::with_status(warp::reply::json(&output), status).into_response()
+}
+
+/// Response for a repeated vote, with entry as it is now. 400 if the name is not in the Tree.
+fn duplicate(entry: Option<Entry>) -> Result<Response, warp::Rejection> {
+    let mut response: Response = match entry {
+        None => vote_response(VoteResult::NotFound),
+        Some(entry) => {
+            warp::reply::with_status(warp::reply::json(&entry), StatusCode::OK).into_response()
+        }
+    };
+
+    response
+        .headers_mut()
+        .insert(VOTE_APPLIED, HeaderValue::from_static("false"));
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use warp::http::StatusCode;
+
+    use crate::{endpoints::rec::post::vote_response, entry::Entry, procedures::vote::VoteResult};
+
     fn new_success() -> VoteResult {
         let entry: Entry = Entry::new("AName".into(), 42);
         let success: VoteResult = VoteResult::Success(entry);
@@ -53,24 +166,11 @@ mod tests {
     mod request_conversion {
         use super::*;
 
-        #[test]
-        #[should_panic(expected = "Unexpected VoteResult. Got Next.")]
-        fn post_result_next_error() {
-            let (_node, next) = new_empty_next();
-
-            // This is expected to panic because VoteResult::Next is not meant to be used by the endpoints
-            let _warp_response: Result<warp::reply::Response, warp::Rejection> = next.into();
-        }
-
         #[test]
         fn post_result_not_found() {
             let not_found: VoteResult = VoteResult::NotFound;
 
-            let warp_response: Result<warp::reply::Response, warp::Rejection> = not_found.into();
-            let warp_response = match warp_response {
-                Err(err) => panic!("Warp Response is Err {:#?} .", err),
-                Ok(value) => value,
-            };
+            let warp_response: warp::reply::Response = vote_response(not_found);
 
             // Status code 400
             let expected_status_code = StatusCode::BAD_REQUEST;
@@ -82,11 +182,7 @@ mod tests {
             let entry: Entry = Entry::new("AName".into(), 32);
             let success: VoteResult = VoteResult::Success(entry);
 
-            let warp_response: Result<warp::reply::Response, warp::Rejection> = success.into();
-            let warp_response = match warp_response {
-                Err(err) => panic!("Warp Response is Err {:#?} .", err),
-                Ok(value) => value,
-            };
+            let warp_response: warp::reply::Response = vote_response(success);
 
             // Status code 201
             let expected_status_code = StatusCode::CREATED;
@@ -94,17 +190,81 @@ mod tests {
         }
     }
 
+    mod dedupe {
+        use super::*;
+
+        use std::time::Duration;
+
+        use crate::{
+            dedupe::{Dedupe, DedupeOptions},
+            endpoints::rec::post::{vote, Input, VOTE_APPLIED},
+            procedures::{find::tree::Find, load::tree::Load},
+            registry::Dictionary,
+            tree::Tree,
+        };
+
+        async fn new_dictionary() -> Dictionary {
+            let tree: Tree = Tree::new_empty(5).await;
+            tree.include(Entry::new("Ann".into(), 10)).unwrap();
+
+            Dictionary {
+                dedupe: Dedupe::new(DedupeOptions {
+                    window: Duration::from_secs(60),
+                    ..DedupeOptions::default()
+                }),
+                ..Dictionary::for_tests(tree)
+            }
+        }
+
+        #[tokio::test]
+        async fn repeated_vote_is_not_counted() {
+            let dictionary: Dictionary = new_dictionary().await;
+            let client = Some("203.0.113.7".parse().unwrap());
+            let request = Input {
+                name: "ann".into(),
+                user: None,
+            };
+
+            let response = vote(request.clone(), client, dictionary.clone())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(response.headers()[VOTE_APPLIED], "true");
+
+            let response = vote(request.clone(), client, dictionary.clone())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[VOTE_APPLIED], "false");
+
+            let times: u64 = *dictionary.current().find("Ann").unwrap().get_times();
+            assert_eq!(times, 11);
+
+            // Another user behind the same address is counted.
+            let request = Input {
+                name: "Ann".into(),
+                user: Some("u-42".into()),
+            };
+            let response = vote(request.clone(), client, dictionary.clone())
+                .await
+                .unwrap();
+            assert_eq!(response.headers()[VOTE_APPLIED], "true");
+
+            // The same user from another address is counted too.
+            let other = Some("203.0.113.8".parse().unwrap());
+            let response = vote(request, other, dictionary.clone()).await.unwrap();
+            assert_eq!(response.headers()[VOTE_APPLIED], "true");
+        }
+    }
+
     mod status_codes {
         use super::*;
         use warp::reply::Response;
-        use warp::Rejection;
 
         #[test]
         fn not_found_is_400() {
             let not_found: VoteResult = VoteResult::NotFound;
-            let response: Result<Response, Rejection> = not_found.into();
-
-            let response: Response = response.unwrap();
+            let response: Response = vote_response(not_found);
 
             assert_eq!(response.status(), StatusCode::BAD_REQUEST);
         }
@@ -112,9 +272,7 @@ mod tests {
         #[test]
         fn success_is_201() {
             let success = new_success();
-            let response: Result<Response, Rejection> = success.into();
-
-            let response: Response = response.unwrap();
+            let response: Response = vote_response(success);
 
             assert_eq!(response.status(), StatusCode::CREATED);
         }
@@ -4,148 +4,133 @@ use percent_encoding_rfc3986::percent_decode;
 
 use crate::{
     entry::Entry,
-    log::log_debug,
-    node::NodeExt,
-    procedures::get::{tree::Get, GetPrefixError, SearchResult},
+    metrics::{GET_ENTRIES_SECONDS, NOT_FOUND},
+    procedures::get::{
+        highlight_prefix, tree::Get, Found, GetPrefixError, Highlighted, OffsetUnit, TagFilter,
+    },
+    registry::Dictionary,
     tree::Tree,
 };
 
-// This is parsed by serde as a single array of Entry
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// Query parameters of the GET endpoints.
+///
+/// - user: If set, the suggestions are re-ranked with the personal votes of this user.
+/// - tag: Comma separated tags. Only entries with all of them are suggested.
+/// - exclude_tag: Comma separated tags. Entries with any of them are not suggested.
+/// - highlight: If set, each suggestion includes the ranges of its name that matched, in these units.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Query {
+    pub user: Option<String>,
+    pub tag: Option<String>,
+    pub exclude_tag: Option<String>,
+    pub highlight: Option<OffsetUnit>,
+}
+
+/// Suggestions for a prefix, as the GET endpoints answer them.
+#[derive(Debug, Serialize)]
 #[serde(untagged)]
-pub enum Output {
-    Values(Vec<Entry>),
+pub enum Suggestions {
+    Entries(Vec<Entry>),
+    Highlighted(Vec<Highlighted>),
 }
 
-impl Default for Output {
-    fn default() -> Self {
-        let values = [];
+impl Suggestions {
+    pub fn len(&self) -> usize {
+        match self {
+            Suggestions::Entries(values) => values.len(),
+            Suggestions::Highlighted(values) => values.len(),
+        }
+    }
+}
 
-        let values: Vec<Entry> = values.into();
+/// Re-rank the entries found for prefix with the personal votes of query.user,
+/// and highlight the matched part of each name if query.highlight is set.
+pub fn suggestions(
+    prefix: &str,
+    found: Found,
+    query: &Query,
+    dictionary: &Dictionary,
+    tree: &Tree,
+    filter: &TagFilter,
+) -> Suggestions {
+    let values: Vec<Entry> = match &query.user {
+        None => found.entries,
+        Some(user) => dictionary
+            .overlay
+            .blend(user, prefix, found.entries, tree, filter),
+    };
 
-        Output::Values(values)
+    match query.highlight {
+        None => Suggestions::Entries(values),
+        Some(unit) => Suggestions::Highlighted(highlight_prefix(found.matched, values, unit)),
     }
 }
 
-pub async fn get_entries(name: String, tree: Tree) -> Result<warp::reply::Json, warp::Rejection> {
-    log_debug("-----------------------------------------------------------------");
-    log_debug(&format!("Parsing {name}"));
+pub async fn get_entries(
+    name: String,
+    query: Query,
+    dictionary: Dictionary,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    let _timer = GET_ENTRIES_SECONDS.start_timer();
 
     // Sometimes names have encoded characters like %%20 for space
     // This will try parsing these entries,
     // if it fails, just use the regular name
     let parsed_name: String = match percent_decode(name.as_bytes()) {
         Err(err) => {
-            log_debug(&format!("Error parsing name {err}"));
+            tracing::debug!("Error parsing name {err}");
             name
         }
         Ok(value) => match value.decode_utf8() {
             Err(err) => {
-                log_debug(&format!("Error parsing name {err}"));
+                tracing::debug!("Error parsing name {err}");
                 name
             }
             Ok(value) => String::from(value),
         },
     };
 
-    println!("Get entry {parsed_name} .");
+    let span = tracing::Span::current();
+    span.record("prefix", parsed_name.as_str());
+
+    let tree: Tree = dictionary.current();
+    let filter = TagFilter::parse(query.tag.as_deref(), query.exclude_tag.as_deref());
 
-    let results = match tree.get_top(&parsed_name) {
+    let found: Found = match tree.search(&parsed_name, &filter) {
         Ok(value) => value,
         Err(err) => match err {
             GetPrefixError::NotFound(value) => {
-                let message = format!("Prefix {value} not found");
-                log_debug(&message);
+                tracing::debug!(prefix = %value, "Prefix not found.");
+                NOT_FOUND.with_label_values(&["get"]).inc();
+                span.record("results", 0);
 
-                return Ok(warp::reply::json(&Output::default()));
+                return Ok(warp::reply::json(&Vec::<Entry>::new()));
             }
         },
     };
 
-    Ok(warp::reply::json(&results))
-}
+    let suggestions: Suggestions =
+        suggestions(&parsed_name, found, &query, &dictionary, &tree, &filter);
 
-pub async fn get_top_entries(tree: Tree) -> Result<warp::reply::Json, warp::Rejection> {
-    get_entries("".into(), tree).await
-}
+    span.record("results", suggestions.len());
 
-impl From<SearchResult> for Output {
-    fn from(search_result: SearchResult) -> Output {
-        match search_result {
-            SearchResult::Next(value) => {
-                // This branch should never happen.
-                // To help debug, collecting information about the node
-                let info: String = match value.upgrade() {
-                    None => "(Not found)".into(),
-                    Some(pointer) => pointer.read().get_prefix().into(),
-                };
+    Ok(warp::reply::json(&suggestions))
+}
 
-                panic!(
-                    "Attempted to convert a SearchResult::Next into an Output. Prefix: {info} ."
-                );
-            }
-            SearchResult::Success(values) => Output::Values(values),
-        }
-    }
+pub async fn get_top_entries(
+    query: Query,
+    dictionary: Dictionary,
+) -> Result<warp::reply::Json, warp::Rejection> {
+    get_entries("".into(), query, dictionary).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use crate::{entry::Entry, node::Node, procedures::get::SearchResult};
-
-    use parking_lot::RwLock;
-    use std::sync::{Arc, Weak};
-
     use std::cmp::Ordering;
 
-    use crate::{
-        procedures::{get::tree::Get, load::tree::Load},
-        tree::Tree,
-    };
-
-    fn new_empty_next() -> SearchResult {
-        // Node will be dropped
-        let node: Arc<RwLock<Node>> = Node::new(None, "".into(), 0, 0);
-        // This pointer will return None when accessed
-        let lock: Weak<RwLock<Node>> = Arc::downgrade(&node);
-        let next: SearchResult = SearchResult::Next(lock);
-
-        next
-    }
-
-    fn new_next() -> (Arc<RwLock<Node>>, SearchResult) {
-        let node: Arc<RwLock<Node>> = Node::new(None, "APrefix".into(), 0, 0);
-        let lock: Weak<RwLock<Node>> = Arc::downgrade(&node);
-        let next: SearchResult = SearchResult::Next(lock);
-
-        (node, next)
-    }
-
-    mod request_conversion {
-        use super::*;
-
-        #[test]
-        #[should_panic(
-            expected = "Attempted to convert a SearchResult::Next into an Output. Prefix: (Not found) ."
-        )]
-        fn get_result_empty_next_error() {
-            let next = new_empty_next();
-
-            let _: Output = next.into();
-        }
-
-        #[test]
-        #[should_panic(
-            expected = "Attempted to convert a SearchResult::Next into an Output. Prefix: APrefix ."
-        )]
-        fn get_result_next_error() {
-            let (_node, next) = new_next();
-
-            let _: Output = next.into();
-        }
-    }
+    use crate::procedures::load::tree::Load;
 
     const RECOMMENDATIONS: usize = 10;
 
@@ -180,7 +165,7 @@ mod tests {
     }
 
     fn assert_entries(tree: &Tree, prefix: &str, expected: Vec<Entry>) {
-        let Output::Values(results) = tree.get_top(prefix).unwrap();
+        let results: Vec<Entry> = tree.get_top(prefix).unwrap();
         let mut valid: bool = expected.len().eq(&results.len());
 
         for i in 0..(expected.len()) {
@@ -265,4 +250,20 @@ mod tests {
 
         assert_entries(&tree, prefix, expected);
     }
+
+    #[tokio::test]
+    async fn search_reports_matched_characters() {
+        let tree: Tree = new_tree().await;
+
+        let found: Found = tree.search("OL", &TagFilter::default()).unwrap();
+        assert_eq!(found.matched, 2);
+
+        let values: Vec<Highlighted> =
+            highlight_prefix(found.matched, found.entries, OffsetUnit::Char);
+        assert_eq!(values[0].entry.get_name(), "oliver");
+        assert_eq!(values[0].highlight[0].end, 2);
+
+        let found: Found = tree.search("", &TagFilter::default()).unwrap();
+        assert_eq!(found.matched, 0);
+    }
 }
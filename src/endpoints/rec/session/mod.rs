@@ -0,0 +1,218 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::sync::watch;
+use warp::{
+    filters::ws::{Message, WebSocket, Ws},
+    reply::Response,
+    Reply,
+};
+
+use crate::{
+    endpoints::{
+        rec::{
+            dictionary::dictionary_not_found,
+            prefix::get::{suggestions, Suggestions},
+        },
+        Query,
+    },
+    metrics::{GET_ENTRIES_SECONDS, NOT_FOUND, SESSIONS},
+    procedures::get::{Cursor, Found, TagFilter},
+    registry::{Dictionary, Registry},
+    tree::Tree,
+};
+
+/// Largest input text a session accepts, in bytes. Longer messages close the session.
+const MAX_MESSAGE_SIZE: usize = 1024;
+
+/// Message sent to the client for each input text that is answered.
+#[derive(Debug, Serialize)]
+struct Output<'a> {
+    text: &'a str,
+    suggestions: Suggestions,
+}
+
+/// GET `/rec/ws`: upgrade to a WebSocket keystroke session on dictionary.
+///
+/// The client sends the whole input text as a text message each time it changes,
+/// and receives the suggestions for it, as the GET endpoints would answer them with query.
+///
+/// Texts that arrive while a response is produced replace each other, and a response is dropped
+/// if a newer text arrived meanwhile, so the client only receives answers for what it currently shows.
+pub async fn session(
+    ws: Ws,
+    query: Query,
+    dictionary: Dictionary,
+) -> Result<Response, warp::Rejection> {
+    let reply = ws
+        .max_message_size(MAX_MESSAGE_SIZE)
+        .on_upgrade(move |socket| run(socket, query, dictionary));
+
+    Ok(reply.into_response())
+}
+
+/// GET `/rec/{dictionary}/ws`.
+pub async fn dictionary_session(
+    name: String,
+    ws: Ws,
+    query: Query,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => session(ws, query, dictionary.clone()).await,
+    }
+}
+
+async fn run(socket: WebSocket, query: Query, dictionary: Dictionary) {
+    SESSIONS.inc();
+
+    let (mut sink, mut stream) = socket.split();
+
+    // Latest input text. Texts that were not answered yet are replaced by newer ones.
+    let (input, mut latest) = watch::channel(String::new());
+
+    // Dropping input when the client leaves ends the answers.
+    let read = async move {
+        while let Some(message) = stream.next().await {
+            match message {
+                Ok(message) if message.is_close() => break,
+                Ok(message) => {
+                    // Pings are answered by warp, binary messages are ignored.
+                    if let Ok(text) = message.to_str() {
+                        input.send_replace(text.into());
+                    }
+                }
+                Err(err) => {
+                    tracing::debug!("Session closed. {err}");
+                    break;
+                }
+            }
+        }
+    };
+
+    let answer = async move {
+        let filter = TagFilter::parse(query.tag.as_deref(), query.exclude_tag.as_deref());
+        let mut cursor = Cursor::new(dictionary.current());
+
+        while latest.changed().await.is_ok() {
+            let text: String = latest.borrow_and_update().clone();
+
+            let timer = GET_ENTRIES_SECONDS.start_timer();
+
+            // After a reload the cursor starts over on the new Tree.
+            let tree: Tree = dictionary.current();
+            cursor.move_to(&tree, &text);
+
+            let found: Found = match cursor.top(&filter) {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::debug!("{err}.");
+                    NOT_FOUND.with_label_values(&["get"]).inc();
+                    Found {
+                        entries: Vec::new(),
+                        matched: 0,
+                    }
+                }
+            };
+
+            let suggestions: Suggestions =
+                suggestions(&text, found, &query, &dictionary, &tree, &filter);
+
+            timer.observe_duration();
+
+            // The client already shows a newer text, answer that one instead.
+            if latest.has_changed().unwrap_or(false) {
+                continue;
+            }
+
+            let output = Output {
+                text: &text,
+                suggestions,
+            };
+            let body: String =
+                serde_json::to_string(&output).expect("Suggestions can be serialized.");
+
+            if let Err(err) = sink.send(Message::text(body)).await {
+                tracing::debug!("Session closed. {err}");
+                break;
+            }
+        }
+    };
+
+    tokio::join!(read, answer);
+
+    SESSIONS.dec();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use serde_json::Value;
+    use warp::Filter;
+
+    use crate::sample::sample_tree;
+
+    async fn new_dictionary() -> Dictionary {
+        Dictionary::for_tests(sample_tree(3).await)
+    }
+
+    fn names(message: &Message) -> (String, Vec<String>) {
+        let output: Value = serde_json::from_str(message.to_str().unwrap()).unwrap();
+        let names: Vec<String> = output["suggestions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["name"].as_str().unwrap().to_string())
+            .collect();
+
+        (output["text"].as_str().unwrap().to_string(), names)
+    }
+
+    #[tokio::test]
+    async fn keystrokes() {
+        let dictionary: Dictionary = new_dictionary().await;
+        let route = warp::path!("rec" / "ws")
+            .and(warp::ws())
+            .and(warp::query::<Query>())
+            .and(warp::any().map(move || dictionary.clone()))
+            .and_then(session);
+
+        let mut client = warp::test::ws()
+            .path("/rec/ws")
+            .handshake(route)
+            .await
+            .unwrap();
+
+        client.send_text("an").await;
+        let (text, suggestions) = names(&client.recv().await.unwrap());
+        assert_eq!(text, "an");
+        assert_eq!(suggestions, ["Anna", "Annabel", "Ann"]);
+
+        client.send_text("anx").await;
+        let (text, suggestions) = names(&client.recv().await.unwrap());
+        assert_eq!(text, "anx");
+        assert!(suggestions.is_empty());
+
+        // Typing faster than the answers, the last text is always answered.
+        for text in ["a", "an", "ann", "anna", "annab"] {
+            client.send_text(text).await;
+        }
+
+        loop {
+            let message = tokio::time::timeout(Duration::from_secs(5), client.recv())
+                .await
+                .expect("The last text was not answered.")
+                .unwrap();
+
+            let (text, suggestions) = names(&message);
+
+            if text == "annab" {
+                assert_eq!(suggestions, ["Annabel"]);
+                break;
+            }
+        }
+    }
+}
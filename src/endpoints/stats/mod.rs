@@ -0,0 +1,37 @@
+use warp::{reply::Response, Reply};
+
+use crate::{
+    endpoints::rec::dictionary::dictionary_not_found,
+    procedures::stats::{tree::Stats, TreeStats},
+    registry::{Dictionary, Registry},
+    tree::Tree,
+};
+
+/// GET `/stats`. Measure the current Tree of the dictionary.
+///
+/// Visits every Node, so it runs on a separate thread to keep the runtime free.
+pub async fn stats(dictionary: Dictionary) -> Result<warp::reply::Json, warp::Rejection> {
+    let tree: Tree = dictionary.current();
+
+    let stats: TreeStats = tokio::task::spawn_blocking(move || tree.stats())
+        .await
+        .expect("Stats thread panicked.");
+
+    tracing::debug!("{stats}");
+
+    Ok(warp::reply::json(&stats))
+}
+
+/// GET `/stats/{dictionary}`.
+pub async fn dictionary_stats(
+    name: String,
+    registry: Registry,
+) -> Result<Response, warp::Rejection> {
+    match registry.get(&name) {
+        None => Ok(dictionary_not_found(&name)),
+        Some(dictionary) => {
+            let reply = stats(dictionary.clone()).await?;
+            Ok(reply.into_response())
+        }
+    }
+}
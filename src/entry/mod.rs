@@ -1,21 +1,55 @@
 use std::{
     cmp::Ordering,
+    collections::BTreeSet,
     fmt::{Debug, Display},
 };
 
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Arbitrary JSON object attached to an Entry, returned along with it.
+pub type Metadata = Map<String, Value>;
 
 /// The main data type stored by the Nodes.
+///
+/// Tags and metadata are not used when comparing entries.
+/// An Entry with more 'times' is greater, and ties are broken by name, ignoring capitalization.
+///
+/// ```
+/// use typerecommendation::Entry;
+///
+/// let ann: Entry = ("Ann", 10).into();
+/// let bob = Entry::new("Bob".into(), 10);
+///
+/// assert!(ann > bob);
+/// assert!(Entry::new("bob".into(), 11) > ann);
+/// assert_eq!(bob, ("BOB", 10).into());
+/// ```
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Entry {
     name: String,
     times: u64,
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    tags: BTreeSet<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Metadata>,
 }
 
 impl Entry {
-    /// Constructs an Entry with given name and times.
+    /// Constructs an Entry with given name and times, no tags and no metadata.
     pub fn new(name: String, times: u64) -> Self {
-        Entry { name, times }
+        Entry {
+            name,
+            times,
+            tags: BTreeSet::new(),
+            metadata: None,
+        }
+    }
+
+    /// Returns this Entry with given tags.
+    pub fn with_tags(mut self, tags: BTreeSet<String>) -> Self {
+        self.tags = tags;
+        self
     }
 
     /// Return a reference to name.
@@ -32,6 +66,32 @@ impl Entry {
     pub fn get_times_mut(&mut self) -> &mut u64 {
         &mut self.times
     }
+
+    /// Return a reference to tags.
+    pub fn get_tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+
+    /// Return a mutable reference to tags.
+    pub fn get_tags_mut(&mut self) -> &mut BTreeSet<String> {
+        &mut self.tags
+    }
+
+    /// Returns this Entry with given metadata.
+    pub fn with_metadata(mut self, metadata: Option<Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Return a reference to metadata.
+    pub fn get_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Return a mutable reference to metadata.
+    pub fn get_metadata_mut(&mut self) -> &mut Option<Metadata> {
+        &mut self.metadata
+    }
 }
 
 // When displaying this error for the user, we need to be able to turn it into a String.
@@ -107,10 +167,7 @@ impl From<(&str, u64)> for Entry {
     fn from(value: (&str, u64)) -> Self {
         let (name, times) = value;
 
-        Entry {
-            name: name.to_string(),
-            times,
-        }
+        Entry::new(name.to_string(), times)
     }
 }
 
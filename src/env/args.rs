@@ -0,0 +1,177 @@
+use crate::env::{
+    load::{is_known_key, Setting, Source},
+    EnvError,
+};
+
+/// Text printed by --help.
+pub const HELP: &str = "\
+Serves name suggestions for a prefix, ranked by votes.
+
+Usage: typerecommendation [OPTIONS]
+
+Options:
+  --config <PATH>     TOML config file, also read from CONFIG_PATH
+  --print-config      Print the effective settings and where each one is set, then exit
+  -h, --help          Print this help, then exit
+  --<setting> <VALUE> Set a setting, e.g. --port 3030 or --personal-weight=2
+
+Settings are read from the config file, then environment variables, then flags,
+each one overriding the previous. Flags are the lowercase environment variable
+names with '-' instead of '_'. In the config file, tables are joined with '_',
+so [personal] weight = 2 sets PERSONAL_WEIGHT.
+
+Settings:
+  HOST                                 Server host, default 0.0.0.0
+  PORT                                 Server port, required
+  SUGGESTION_NUMBER                    Maximum number of suggestions per request, required
+  DATA_PATH                            JSON file loaded on start up, default ./names.json
+  BAD_RECORDS                          skip or abort on invalid records, default skip
+  ALLOW_MISSING_DATA                   Start empty if the data file doesn't exist, default false
+  RELOAD_KEEP_VOTES                    Carry votes over on reload, default true
+  DICTIONARIES                         Comma separated names of the dictionaries to serve
+  DEFAULT_DICTIONARY                   Dictionary of the routes without a name, default the first one
+  DICTIONARY_{NAME}_PATH               Data file of dictionary NAME, default ./{name}.json
+  DICTIONARY_{NAME}_SUGGESTION_NUMBER  Suggestions of dictionary NAME, default SUGGESTION_NUMBER
+  PERSONAL_WEIGHT                      Weight of each personal vote, default 100
+  PERSONAL_MAX_USERS                   Users whose votes are kept, default 10000
+  PERSONAL_MAX_NAMES                   Names kept per user, default 100
+  LOG_LEVEL                            Level or module=level directives, default info,warp=warn
+  LOG_FORMAT                           human or json, default human
+  ACCESS_LOG_PATH                      File the access log is appended to, default none
+  ACCESS_LOG_FORMAT                    combined or json, default combined
+  VOTE_RATE_LIMIT                      Votes per second per client, default 0 (no limit)
+  VOTE_RATE_BURST                      Votes a client can make at once, default 10
+  VOTE_RATE_PER_NAME                   Limit each client separately for each name, default false
+  VOTE_RATE_IDLE_SECONDS               Seconds until an idle client is forgotten, default 300
+  VOTE_RATE_MAX_ENTRIES                Clients, or clients and names, limited at once, default 100000
+  TRUSTED_PROXIES                      Comma separated proxy addresses whose X-Forwarded-For is used
+  VOTE_DEDUPE_SECONDS                  Repeated votes for a name within this time count once, default 0
+  VOTE_DEDUPE_MAX_ENTRIES              Recent votes remembered for VOTE_DEDUPE_SECONDS, default 100000
+  API_KEYS                             Comma separated key:role, role read, vote or admin, default none
+  API_PUBLIC_READ                      Routes that only read don't need a key, default false
+  CORS_ORIGINS                         Comma separated origins that can call /rec from a browser, or *
+  CORS_METHODS                         Methods allowed from those origins, default GET,POST
+  CORS_HEADERS                         Request headers allowed from those origins
+  CORS_MAX_AGE                         Seconds browsers cache a preflight response, default 600
+  TLS_CERT_PATH                        PEM certificate chain, serves HTTPS when set with TLS_KEY_PATH
+  TLS_KEY_PATH                         PEM private key of the certificate
+  GRPC_PORT                            Port of the gRPC service, default none (not served)
+";
+
+/// Command line arguments.
+#[derive(Debug, Default)]
+pub struct Args {
+    /// Path of the config file given with --config.
+    pub config: Option<String>,
+    /// Print HELP and exit.
+    pub help: bool,
+    /// Print the effective settings and exit.
+    pub print_config: bool,
+    /// Settings given as flags.
+    pub flags: Vec<Setting>,
+}
+
+impl Args {
+    /// Parse args, without the program name.
+    ///
+    /// Flags take their value as the next argument or after '=', e.g. `--port 3030` or `--port=3030`.
+    /// A next argument starting with "--" is another flag, not a value.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, EnvError> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter().peekable();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => {
+                    parsed.help = true;
+                    continue;
+                }
+                "--print-config" => {
+                    parsed.print_config = true;
+                    continue;
+                }
+                _ => {}
+            }
+
+            let (flag, value): (String, Option<String>) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.into(), Some(value.into())),
+                None => (arg, None),
+            };
+
+            let name: &str = match flag.strip_prefix("--") {
+                Some(value) if !value.is_empty() => value,
+                _ => return Err(EnvError::UnknownKey(flag)),
+            };
+
+            let key: String = name.replace('-', "_").to_ascii_uppercase();
+
+            if flag != "--config" && !is_known_key(&key) {
+                return Err(EnvError::UnknownKey(flag));
+            }
+
+            let value: String = match value.or_else(|| args.next_if(|next| !next.starts_with("--")))
+            {
+                Some(value) => value,
+                None => return Err(EnvError::MissingValue(flag)),
+            };
+
+            if flag == "--config" {
+                parsed.config = Some(value);
+                continue;
+            }
+
+            parsed.flags.push(Setting {
+                key,
+                value,
+                source: Source::Flag(flag),
+            });
+        }
+
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, EnvError> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn flags() {
+        let args: Args = parse(&[
+            "--config",
+            "config.toml",
+            "--port=3030",
+            "--personal-weight",
+            "2",
+            "--print-config",
+        ])
+        .unwrap();
+
+        assert_eq!(args.config.as_deref(), Some("config.toml"));
+        assert!(args.print_config);
+        assert!(!args.help);
+        assert_eq!(args.flags[0].key, "PORT");
+        assert_eq!(args.flags[0].value, "3030");
+        assert_eq!(args.flags[1].key, "PERSONAL_WEIGHT");
+        assert_eq!(
+            args.flags[1].source,
+            Source::Flag("--personal-weight".into())
+        );
+
+        assert!(parse(&["-h"]).unwrap().help);
+        assert!(matches!(
+            parse(&["--prot", "1"]),
+            Err(EnvError::UnknownKey(_))
+        ));
+        assert!(matches!(parse(&["3030"]), Err(EnvError::UnknownKey(_))));
+        assert!(matches!(parse(&["--port"]), Err(EnvError::MissingValue(_))));
+        assert!(matches!(
+            parse(&["--port", "--print-config"]),
+            Err(EnvError::MissingValue(flag)) if flag == "--port"
+        ));
+    }
+}
@@ -1,10 +1,22 @@
 use std::fmt::Display;
 
+/// Errors loading the configuration.
+///
+/// Keys are named as they were set: `PORT` for the environment, `--port` for a flag,
+/// or `port in config.toml` for the config file.
 #[derive(Debug)]
 pub enum EnvError {
     NotFound(String),
     InvalidValueUnicode(String),
     InvalidValueNumber(String),
+    // Key and a description of the values it accepts
+    InvalidValue(String, String),
+    // Key that is not a setting
+    UnknownKey(String),
+    // Flag given without a value
+    MissingValue(String),
+    // Path of the config file and the reason it couldn't be read
+    InvalidFile(String, String),
 }
 
 impl Display for EnvError {
@@ -15,6 +27,14 @@ impl Display for EnvError {
                 write!(f, "Value for key {} has an invalid Unicode format", key)
             }
             EnvError::InvalidValueNumber(key) => write!(f, "Invalid number format for key {}", key),
+            EnvError::InvalidValue(key, expected) => {
+                write!(f, "Invalid value for key {}. Expected {}", key, expected)
+            }
+            EnvError::UnknownKey(key) => write!(f, "Unknown key {}, see --help", key),
+            EnvError::MissingValue(flag) => write!(f, "Missing value for flag {}", flag),
+            EnvError::InvalidFile(path, reason) => {
+                write!(f, "Failed to read config file {}. {}", path, reason)
+            }
         }
     }
 }
@@ -0,0 +1,141 @@
+use std::fs;
+
+use toml::{Table, Value};
+
+use crate::env::{
+    load::{is_known_key, Setting, Source},
+    EnvError,
+};
+
+/// Read the settings in the TOML config file at path.
+///
+/// Tables are flattened into the environment variable names, so `[personal] weight = 2`
+/// sets PERSONAL_WEIGHT and `[dictionary.fruits] path = "f.json"` sets DICTIONARY_FRUITS_PATH.
+/// Lists are joined with commas, so `dictionaries = ["fruits", "cities"]` sets DICTIONARIES.
+pub fn read_file(path: &str) -> Result<Vec<Setting>, EnvError> {
+    let content: String = fs::read_to_string(path)
+        .map_err(|err| EnvError::InvalidFile(path.into(), err.to_string()))?;
+
+    parse_file(path, &content)
+}
+
+/// Parse content, the TOML config file read from path.
+pub fn parse_file(path: &str, content: &str) -> Result<Vec<Setting>, EnvError> {
+    let table: Table = content
+        .parse()
+        .map_err(|err: toml::de::Error| EnvError::InvalidFile(path.into(), err.message().into()))?;
+
+    let mut settings: Vec<Setting> = Vec::new();
+    flatten(path, "", &table, &mut settings)?;
+
+    Ok(settings)
+}
+
+fn flatten(
+    path: &str,
+    prefix: &str,
+    table: &Table,
+    settings: &mut Vec<Setting>,
+) -> Result<(), EnvError> {
+    for (name, value) in table {
+        let dotted: String = match prefix.is_empty() {
+            true => name.clone(),
+            false => format!("{prefix}.{name}"),
+        };
+
+        if let Value::Table(table) = value {
+            flatten(path, &dotted, table, settings)?;
+            continue;
+        }
+
+        let key: String = dotted.replace(['.', '-'], "_").to_ascii_uppercase();
+
+        if !is_known_key(&key) {
+            return Err(EnvError::UnknownKey(format!("{dotted} in {path}")));
+        }
+
+        let value: String = match value {
+            Value::Array(values) => values
+                .iter()
+                .map(scalar)
+                .collect::<Option<Vec<String>>>()
+                .map(|values| values.join(",")),
+            value => scalar(value),
+        }
+        .ok_or_else(|| {
+            EnvError::InvalidValue(
+                format!("{dotted} in {path}"),
+                "a value or a list of values".into(),
+            )
+        })?;
+
+        settings.push(Setting {
+            key,
+            value,
+            source: Source::File {
+                path: path.into(),
+                key: dotted,
+            },
+        });
+    }
+
+    Ok(())
+}
+
+/// Value as the text of an environment variable, or None if it is a list or a table.
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Integer(value) => Some(value.to_string()),
+        Value::Float(value) => Some(value.to_string()),
+        Value::Boolean(value) => Some(value.to_string()),
+        Value::Datetime(value) => Some(value.to_string()),
+        Value::Array(_) | Value::Table(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flattens_tables_and_lists() {
+        let content = r#"
+            port = 3030
+            dictionaries = ["fruits", "cities"]
+
+            [personal]
+            weight = 2.5
+
+            [dictionary.fruits]
+            path = "./fruits.json"
+            suggestion_number = 5
+        "#;
+
+        let settings: Vec<Setting> = parse_file("config.toml", content).unwrap();
+        let value = |key: &str| -> &str {
+            &settings
+                .iter()
+                .find(|setting| setting.key == key)
+                .unwrap()
+                .value
+        };
+
+        assert_eq!(value("PORT"), "3030");
+        assert_eq!(value("DICTIONARIES"), "fruits,cities");
+        assert_eq!(value("PERSONAL_WEIGHT"), "2.5");
+        assert_eq!(value("DICTIONARY_FRUITS_PATH"), "./fruits.json");
+        assert_eq!(value("DICTIONARY_FRUITS_SUGGESTION_NUMBER"), "5");
+
+        let unknown = parse_file("config.toml", "[personal]\nwieght = 2").unwrap_err();
+        assert_eq!(
+            unknown.to_string(),
+            "Unknown key personal.wieght in config.toml, see --help"
+        );
+
+        assert!(matches!(
+            parse_file("config.toml", "port = "),
+            Err(EnvError::InvalidFile(_, _))
+        ));
+    }
+}
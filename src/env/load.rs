@@ -1,21 +1,267 @@
-use std::env;
+use std::{collections::HashMap, env, fmt::Display, str::FromStr};
 
-use crate::{env::EnvError, log::log_debug};
+use crate::env::EnvError;
 
-/// Return environment variable.
-pub fn load_env_var(key: &str) -> Result<String, EnvError> {
-    match env::var(key) {
-        Ok(value) => {
-            log_debug(&format!("Environment Variable {key}: {value}"));
-            Ok(value)
+/// Settings that can be set in every layer, by their environment variable name.
+///
+/// Dictionary settings DICTIONARY_{NAME}_PATH and DICTIONARY_{NAME}_SUGGESTION_NUMBER are also accepted.
+pub const KEYS: [&str; 33] = [
+    "HOST",
+    "PORT",
+    "SUGGESTION_NUMBER",
+    "DATA_PATH",
+    "BAD_RECORDS",
+    "ALLOW_MISSING_DATA",
+    "RELOAD_KEEP_VOTES",
+    "DICTIONARIES",
+    "DEFAULT_DICTIONARY",
+    "PERSONAL_WEIGHT",
+    "PERSONAL_MAX_USERS",
+    "PERSONAL_MAX_NAMES",
+    "LOG_LEVEL",
+    "LOG_FORMAT",
+    "ACCESS_LOG_PATH",
+    "ACCESS_LOG_FORMAT",
+    "VOTE_RATE_LIMIT",
+    "VOTE_RATE_BURST",
+    "VOTE_RATE_PER_NAME",
+    "VOTE_RATE_IDLE_SECONDS",
+    "VOTE_RATE_MAX_ENTRIES",
+    "TRUSTED_PROXIES",
+    "VOTE_DEDUPE_SECONDS",
+    "VOTE_DEDUPE_MAX_ENTRIES",
+    "API_KEYS",
+    "API_PUBLIC_READ",
+    "CORS_ORIGINS",
+    "CORS_METHODS",
+    "CORS_HEADERS",
+    "CORS_MAX_AGE",
+    "TLS_CERT_PATH",
+    "TLS_KEY_PATH",
+    "GRPC_PORT",
+];
+
+/// Returns true if key is the environment variable name of a setting.
+pub fn is_known_key(key: &str) -> bool {
+    if KEYS.contains(&key) {
+        return true;
+    }
+
+    match key.strip_prefix("DICTIONARY_") {
+        None => false,
+        Some(rest) => [
+            rest.strip_suffix("_PATH"),
+            rest.strip_suffix("_SUGGESTION_NUMBER"),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|name| !name.is_empty()),
+    }
+}
+
+/// Where the value of a setting comes from.
+///
+/// - Default: Not set anywhere, the built-in default is used.
+/// - File: The config file at path, with key as written in it.
+/// - Env: An environment variable.
+/// - Flag: A command line flag, as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File { path: String, key: String },
+    Env,
+    Flag(String),
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File { path, key } => write!(f, "file {path} ({key})"),
+            Source::Env => write!(f, "env"),
+            Source::Flag(flag) => write!(f, "flag {flag}"),
+        }
+    }
+}
+
+/// A value for a setting, by its environment variable name, and where it was set.
+#[derive(Debug, Clone)]
+pub struct Setting {
+    pub key: String,
+    pub value: String,
+    pub source: Source,
+}
+
+/// Values of the settings from every source.
+///
+/// Flags override environment variables, which override the config file.
+#[derive(Debug, Default)]
+pub struct Layers {
+    file: HashMap<String, Setting>,
+    // None if the value is not valid Unicode.
+    env: HashMap<String, Option<String>>,
+    flags: HashMap<String, Setting>,
+}
+
+impl Layers {
+    pub fn new(
+        file: Vec<Setting>,
+        env: HashMap<String, Option<String>>,
+        flags: Vec<Setting>,
+    ) -> Self {
+        let by_key = |settings: Vec<Setting>| -> HashMap<String, Setting> {
+            settings
+                .into_iter()
+                .map(|setting| (setting.key.clone(), setting))
+                .collect()
+        };
+
+        Layers {
+            file: by_key(file),
+            env,
+            flags: by_key(flags),
+        }
+    }
+
+    /// Where the value of key comes from.
+    pub fn source(&self, key: &str) -> Source {
+        if let Some(setting) = self.flags.get(key) {
+            return setting.source.clone();
+        }
+
+        if self.env.contains_key(key) {
+            return Source::Env;
+        }
+
+        match self.file.get(key) {
+            Some(setting) => setting.source.clone(),
+            None => Source::Default,
+        }
+    }
+
+    /// Name of key as it was set, for error messages.
+    pub fn name(&self, key: &str) -> String {
+        match self.source(key) {
+            Source::Default | Source::Env => key.into(),
+            Source::File { path, key } => format!("{key} in {path}"),
+            Source::Flag(flag) => flag,
+        }
+    }
+
+    /// Error for a value of key that is not one of the expected values.
+    pub fn invalid(&self, key: &str, expected: &str) -> EnvError {
+        EnvError::InvalidValue(self.name(key), expected.into())
+    }
+
+    /// Return the value of setting key from the source with the highest priority.
+    pub fn load_var(&self, key: &str) -> Result<String, EnvError> {
+        if let Some(setting) = self.flags.get(key) {
+            return Ok(setting.value.clone());
+        }
+
+        match self.env.get(key) {
+            Some(Some(value)) => return Ok(value.clone()),
+            Some(None) => return Err(EnvError::InvalidValueUnicode(key.into())),
+            None => {}
+        }
+
+        match self.file.get(key) {
+            Some(setting) => Ok(setting.value.clone()),
+            None => Err(EnvError::NotFound(key.into())),
+        }
+    }
+
+    /// Return setting key parsed as T, or None if it is not set.
+    ///
+    /// expected describes the accepted values, and is used in the error message.
+    pub fn load_parse<T: FromStr>(&self, key: &str, expected: &str) -> Result<Option<T>, EnvError> {
+        let value: String = match self.load_var(key) {
+            Ok(value) => value,
+            Err(EnvError::NotFound(_)) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        match value.trim().parse::<T>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => Err(self.invalid(key, expected)),
         }
-        Err(err) => {
-            let key = String::from(key);
+    }
+}
 
-            match err {
-                env::VarError::NotPresent => Err(EnvError::NotFound(key)),
-                env::VarError::NotUnicode(_) => Err(EnvError::InvalidValueUnicode(key)),
-            }
+/// Return the environment variables of the process.
+pub fn env_vars() -> HashMap<String, Option<String>> {
+    env::vars_os()
+        .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setting(key: &str, value: &str, source: Source) -> Setting {
+        Setting {
+            key: key.into(),
+            value: value.into(),
+            source,
         }
     }
+
+    #[test]
+    fn layers() {
+        let file_source = Source::File {
+            path: "config.toml".into(),
+            key: "port".into(),
+        };
+        let file = Vec::from([
+            setting("PORT", "1000", file_source.clone()),
+            setting(
+                "PERSONAL_WEIGHT",
+                "heavy",
+                Source::File {
+                    path: "config.toml".into(),
+                    key: "personal.weight".into(),
+                },
+            ),
+        ]);
+        let env = HashMap::from([("PORT".to_string(), Some("2000".to_string()))]);
+        let flags = Vec::from([setting("HOST", "::1", Source::Flag("--host".into()))]);
+
+        let layers = Layers::new(file.clone(), env.clone(), flags);
+        assert_eq!(layers.load_var("PORT").unwrap(), "2000");
+        assert_eq!(layers.source("PORT"), Source::Env);
+        assert_eq!(layers.load_var("HOST").unwrap(), "::1");
+        assert_eq!(layers.name("HOST"), "--host");
+        assert_eq!(layers.source("LOG_LEVEL"), Source::Default);
+        assert!(matches!(
+            layers.load_var("LOG_LEVEL"),
+            Err(EnvError::NotFound(_))
+        ));
+
+        let flags = Vec::from([setting("PORT", "3000", Source::Flag("--port".into()))]);
+        let layers = Layers::new(file.clone(), env, flags);
+        assert_eq!(
+            layers.load_parse::<u16>("PORT", "a port").unwrap(),
+            Some(3000)
+        );
+
+        let layers = Layers::new(file, HashMap::new(), Vec::new());
+        assert_eq!(layers.source("PORT"), file_source);
+        assert_eq!(
+            layers
+                .load_parse::<f64>("PERSONAL_WEIGHT", "a number")
+                .unwrap_err()
+                .to_string(),
+            "Invalid value for key personal.weight in config.toml. Expected a number"
+        );
+    }
+
+    #[test]
+    fn known_keys() {
+        assert!(is_known_key("PORT"));
+        assert!(is_known_key("DICTIONARY_FRUITS_PATH"));
+        assert!(is_known_key("DICTIONARY_FRUITS_SUGGESTION_NUMBER"));
+        assert!(!is_known_key("DICTIONARY__PATH"));
+        assert!(!is_known_key("PROT"));
+    }
 }
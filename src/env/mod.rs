@@ -1,61 +1,672 @@
+mod args;
 mod error;
+mod file;
 mod load;
 
+pub use args::{Args, HELP};
 use error::EnvError;
-use load::load_env_var;
+use file::read_file;
+use load::{env_vars, Layers, Source};
 
-pub type SuggestionNumber = usize;
+use std::{collections::HashMap, net::IpAddr, time::Duration};
+
+use crate::{
+    auth::{AuthOptions, Role},
+    dedupe::DedupeOptions,
+    limit::RateLimitOptions,
+    log::{LogFormat, LogOptions},
+    personal::PersonalOptions,
+    procedures::load::{BadRecordPolicy, LoadOptions},
+    registry::{is_valid_name, DictionaryConfig, DEFAULT_DICTIONARY},
+    server::{is_valid_origin, AccessLogFormat, AccessLogOptions, CorsOptions, TlsOptions},
+};
+
+pub use crate::tree::SuggestionNumber;
 
 // We could make it just a return a tuple of values like this (host, port, suggestion_number)
 // But if we accidentally mixed the values, it could lead to hard-to-find bugs.
 
-/// Contains environment variables HOST, PORT and SUGGESTION_NUMBER.
+/// Contains settings HOST and PORT,
+/// and the settings for loading and reloading each dictionary.
 pub struct EnvVars {
     pub host: String,
     pub port: String,
-    pub suggestion_number: SuggestionNumber,
+    pub dictionaries: Vec<DictionaryConfig>,
+    pub default_dictionary: String,
+    pub keep_votes: bool,
+    pub personal: PersonalOptions,
+    pub log: LogOptions,
+    pub access_log: AccessLogOptions,
+    pub rate_limit: RateLimitOptions,
+    pub trusted_proxies: Vec<IpAddr>,
+    pub dedupe: DedupeOptions,
+    pub auth: AuthOptions,
+    pub cors: CorsOptions,
+    pub tls: Option<TlsOptions>,
+    pub grpc_port: Option<u16>,
+    // Where each setting was read from, and the effective values, for --print-config.
+    layers: Layers,
+    effective: Vec<(String, String)>,
 }
 
 impl EnvVars {
-    /// Load Environment variables: HOST, PORT and SUGGESTION_NUMBER.
+    /// Load settings HOST, PORT and SUGGESTION_NUMBER.
+    ///
+    /// Each setting is read from the flags in args, then the environment, then the config file
+    /// given with --config or CONFIG_PATH, and the first one found is used.
+    ///
+    /// Optional variables DATA_PATH, BAD_RECORDS and ALLOW_MISSING_DATA set how the data file is loaded.
+    /// Optional variable RELOAD_KEEP_VOTES sets if votes are carried over when the data file is reloaded.
+    /// Optional variables DICTIONARIES and DEFAULT_DICTIONARY set the named dictionaries, see load_dictionaries.
+    /// Optional variables PERSONAL_WEIGHT, PERSONAL_MAX_USERS and PERSONAL_MAX_NAMES set the per-user overlay.
+    /// Optional variables LOG_LEVEL and LOG_FORMAT set which events are logged, and how.
+    /// Optional variables ACCESS_LOG_PATH and ACCESS_LOG_FORMAT set the access log file.
+    /// Optional variables VOTE_RATE_LIMIT, VOTE_RATE_BURST, VOTE_RATE_PER_NAME, VOTE_RATE_IDLE_SECONDS
+    /// and VOTE_RATE_MAX_ENTRIES set the vote rate limit.
+    /// Optional variable TRUSTED_PROXIES sets the proxies whose X-Forwarded-For header is used.
+    /// Optional variables VOTE_DEDUPE_SECONDS and VOTE_DEDUPE_MAX_ENTRIES set the duplicate vote window.
+    /// Optional variables API_KEYS and API_PUBLIC_READ set the API keys needed by the routes.
+    /// Optional variables CORS_ORIGINS, CORS_METHODS, CORS_HEADERS and CORS_MAX_AGE set which browser origins can call /rec.
+    /// Optional variables TLS_CERT_PATH and TLS_KEY_PATH set the certificate to serve HTTPS with.
+    /// Optional variable GRPC_PORT sets the port of the gRPC service, which is not served without it.
     ///
     /// Compiles differently depending on setting:
     /// - dev: Load from local .env file.
     /// - test: Set specific values for testing.
     /// - release: Environment values must be set before running.
-    pub fn new() -> Result<EnvVars, EnvError> {
+    pub fn load(args: &Args) -> Result<EnvVars, EnvError> {
         load_locally();
 
-        let host: String = match load_env_var("HOST") {
+        let env = env_vars();
+
+        let config_path: Option<String> = match &args.config {
+            Some(value) => Some(value.clone()),
+            None => env.get("CONFIG_PATH").cloned().flatten(),
+        };
+
+        let file = match config_path {
+            Some(path) => read_file(&path)?,
+            None => Vec::new(),
+        };
+
+        let layers = Layers::new(file, env, args.flags.clone());
+
+        let host: String = match layers.load_var("HOST") {
             Ok(value) => value,
             Err(_) => "0.0.0.0".to_string(),
         };
 
-        let port: String = load_env_var("PORT")?;
-        let suggestion_number: String = load_env_var("SUGGESTION_NUMBER")?;
+        let port: String = layers.load_var("PORT")?;
+
+        if port.trim().parse::<u16>().is_err() {
+            return Err(layers.invalid("PORT", "a port number"));
+        }
 
-        let suggestion_number = match suggestion_number.parse::<SuggestionNumber>() {
+        let suggestion_number: String = layers.load_var("SUGGESTION_NUMBER")?;
+
+        let suggestion_number = match suggestion_number.trim().parse::<SuggestionNumber>() {
             Ok(value) => value,
             Err(_) => {
-                return Err(EnvError::InvalidValueNumber(String::from(
-                    "SUGGESTION_NUMBER",
-                )))
+                return Err(EnvError::InvalidValueNumber(
+                    layers.name("SUGGESTION_NUMBER"),
+                ))
             }
         };
 
+        let load_options: LoadOptions = load_options(&layers)?;
+        let (dictionaries, default_dictionary) =
+            load_dictionaries(&layers, suggestion_number, load_options.clone())?;
+
+        let keep_votes: bool = layers
+            .load_parse("RELOAD_KEEP_VOTES", "true or false")?
+            .unwrap_or(true);
+
+        let personal: PersonalOptions = personal_options(&layers)?;
+        let log: LogOptions = log_options(&layers)?;
+        let access_log: AccessLogOptions = access_log_options(&layers)?;
+        let rate_limit: RateLimitOptions = rate_limit_options(&layers)?;
+        let trusted_proxies: Vec<IpAddr> = trusted_proxies(&layers)?;
+        let dedupe: DedupeOptions = dedupe_options(&layers)?;
+        let auth: AuthOptions = auth_options(&layers)?;
+        let cors: CorsOptions = cors_options(&layers)?;
+        let tls: Option<TlsOptions> = tls_options(&layers)?;
+        let grpc_port: Option<u16> = layers.load_parse("GRPC_PORT", "a port number")?;
+
+        let mut effective: Vec<(String, String)> = Vec::from([
+            ("HOST".into(), host.clone()),
+            ("PORT".into(), port.clone()),
+            ("SUGGESTION_NUMBER".into(), suggestion_number.to_string()),
+            ("DATA_PATH".into(), load_options.path),
+            ("BAD_RECORDS".into(), load_options.bad_records.to_string()),
+            (
+                "ALLOW_MISSING_DATA".into(),
+                load_options.allow_missing.to_string(),
+            ),
+            ("RELOAD_KEEP_VOTES".into(), keep_votes.to_string()),
+            (
+                "DICTIONARIES".into(),
+                dictionaries
+                    .iter()
+                    .map(|d| d.name.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(","),
+            ),
+            ("DEFAULT_DICTIONARY".into(), default_dictionary.clone()),
+        ]);
+
+        // Without DICTIONARIES, the single dictionary uses the settings above.
+        if layers.load_var("DICTIONARIES").is_ok() {
+            for dictionary in &dictionaries {
+                let key: String = dictionary_key(&dictionary.name);
+
+                effective.push((
+                    format!("DICTIONARY_{key}_PATH"),
+                    dictionary.load_options.path.clone(),
+                ));
+                effective.push((
+                    format!("DICTIONARY_{key}_SUGGESTION_NUMBER"),
+                    dictionary.suggestion_number.to_string(),
+                ));
+            }
+        }
+
+        effective.extend([
+            ("PERSONAL_WEIGHT".into(), personal.weight.to_string()),
+            ("PERSONAL_MAX_USERS".into(), personal.max_users.to_string()),
+            ("PERSONAL_MAX_NAMES".into(), personal.max_names.to_string()),
+            ("LOG_LEVEL".into(), log.filter.clone()),
+            ("LOG_FORMAT".into(), log.format.to_string()),
+            (
+                "ACCESS_LOG_PATH".into(),
+                access_log.path.clone().unwrap_or_default(),
+            ),
+            ("ACCESS_LOG_FORMAT".into(), access_log.format.to_string()),
+            ("VOTE_RATE_LIMIT".into(), rate_limit.rate.to_string()),
+            ("VOTE_RATE_BURST".into(), rate_limit.burst.to_string()),
+            ("VOTE_RATE_PER_NAME".into(), rate_limit.per_name.to_string()),
+            (
+                "VOTE_RATE_IDLE_SECONDS".into(),
+                rate_limit.idle.as_secs().to_string(),
+            ),
+            (
+                "VOTE_RATE_MAX_ENTRIES".into(),
+                rate_limit.max_entries.to_string(),
+            ),
+            (
+                "TRUSTED_PROXIES".into(),
+                trusted_proxies
+                    .iter()
+                    .map(IpAddr::to_string)
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            (
+                "VOTE_DEDUPE_SECONDS".into(),
+                dedupe.window.as_secs_f64().to_string(),
+            ),
+            (
+                "VOTE_DEDUPE_MAX_ENTRIES".into(),
+                dedupe.max_entries.to_string(),
+            ),
+            // Keys are secret, only their roles are shown.
+            ("API_KEYS".into(), {
+                let mut roles: Vec<Role> = auth.keys.values().copied().collect();
+                roles.sort();
+
+                roles
+                    .iter()
+                    .map(|role| format!("***:{role}"))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            }),
+            ("API_PUBLIC_READ".into(), auth.public_read.to_string()),
+            ("CORS_ORIGINS".into(), cors.origins.join(",")),
+            (
+                "CORS_METHODS".into(),
+                cors.methods
+                    .iter()
+                    .map(|method| method.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(","),
+            ),
+            (
+                "CORS_HEADERS".into(),
+                cors.headers
+                    .iter()
+                    .map(|header| header.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(","),
+            ),
+            ("CORS_MAX_AGE".into(), cors.max_age.to_string()),
+            (
+                "TLS_CERT_PATH".into(),
+                tls.as_ref()
+                    .map(|tls| tls.cert_path.clone())
+                    .unwrap_or_default(),
+            ),
+            (
+                "TLS_KEY_PATH".into(),
+                tls.as_ref()
+                    .map(|tls| tls.key_path.clone())
+                    .unwrap_or_default(),
+            ),
+            (
+                "GRPC_PORT".into(),
+                grpc_port.map(|port| port.to_string()).unwrap_or_default(),
+            ),
+        ]);
+
         Ok(EnvVars {
             host,
             port,
-            suggestion_number,
+            dictionaries,
+            default_dictionary,
+            keep_votes,
+            personal,
+            log,
+            access_log,
+            rate_limit,
+            trusted_proxies,
+            dedupe,
+            auth,
+            cors,
+            tls,
+            grpc_port,
+            layers,
+            effective,
         })
     }
+
+    /// Effective settings, one per line, with where each one was set.
+    ///
+    /// Settings that were not set show their default value.
+    pub fn print_config(&self) -> String {
+        let width: usize = self
+            .effective
+            .iter()
+            .map(|(key, _)| key.len())
+            .max()
+            .unwrap_or_default();
+
+        self.effective
+            .iter()
+            .map(|(key, value)| {
+                let source: Source = self.layers.source(key);
+                format!("{key:width$} = {value:?}  # {source}\n")
+            })
+            .collect()
+    }
+}
+
+/// Name of a dictionary as used in its setting names: uppercase, with '-' replaced by '_'.
+fn dictionary_key(name: &str) -> String {
+    name.to_ascii_uppercase().replace('-', "_")
+}
+
+/// Load the optional variables DATA_PATH, BAD_RECORDS and ALLOW_MISSING_DATA.
+///
+/// Variables that are not set keep the default LoadOptions.
+fn load_options(layers: &Layers) -> Result<LoadOptions, EnvError> {
+    let mut options = LoadOptions::default();
+
+    if let Ok(value) = layers.load_var("DATA_PATH") {
+        options.path = value;
+    }
+
+    if let Some(value) = layers.load_parse::<BadRecordPolicy>("BAD_RECORDS", "skip or abort")? {
+        options.bad_records = value;
+    }
+
+    if let Some(value) = layers.load_parse::<bool>("ALLOW_MISSING_DATA", "true or false")? {
+        options.allow_missing = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables PERSONAL_WEIGHT, PERSONAL_MAX_USERS and PERSONAL_MAX_NAMES.
+///
+/// Variables that are not set keep the default PersonalOptions.
+fn personal_options(layers: &Layers) -> Result<PersonalOptions, EnvError> {
+    let mut options = PersonalOptions::default();
+
+    if let Some(value) = layers.load_parse::<f64>("PERSONAL_WEIGHT", "a non-negative number")? {
+        if !value.is_finite() || value < 0.0 {
+            return Err(layers.invalid("PERSONAL_WEIGHT", "a non-negative number"));
+        }
+
+        options.weight = value;
+    }
+
+    if let Some(value) = layers.load_parse::<usize>("PERSONAL_MAX_USERS", "a number")? {
+        options.max_users = value;
+    }
+
+    if let Some(value) = layers.load_parse::<usize>("PERSONAL_MAX_NAMES", "a number")? {
+        options.max_names = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables LOG_LEVEL and LOG_FORMAT.
+///
+/// LOG_LEVEL is a level, or a comma separated list of level and module=level directives.
+/// Variables that are not set keep the default LogOptions.
+fn log_options(layers: &Layers) -> Result<LogOptions, EnvError> {
+    let mut options = LogOptions::default();
+
+    if let Ok(value) = layers.load_var("LOG_LEVEL") {
+        if !LogOptions::is_valid_filter(&value) {
+            return Err(layers.invalid(
+                "LOG_LEVEL",
+                "a level, or a comma separated list of level and module=level directives",
+            ));
+        }
+
+        options.filter = value;
+    }
+
+    if let Some(value) = layers.load_parse::<LogFormat>("LOG_FORMAT", "human or json")? {
+        options.format = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables ACCESS_LOG_PATH and ACCESS_LOG_FORMAT.
+///
+/// If ACCESS_LOG_PATH is not set, there is no access log.
+fn access_log_options(layers: &Layers) -> Result<AccessLogOptions, EnvError> {
+    let mut options = AccessLogOptions::default();
+
+    if let Ok(value) = layers.load_var("ACCESS_LOG_PATH") {
+        options.path = Some(value);
+    }
+
+    if let Some(value) =
+        layers.load_parse::<AccessLogFormat>("ACCESS_LOG_FORMAT", "combined or json")?
+    {
+        options.format = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables VOTE_RATE_LIMIT, VOTE_RATE_BURST, VOTE_RATE_PER_NAME, VOTE_RATE_IDLE_SECONDS
+/// and VOTE_RATE_MAX_ENTRIES.
+///
+/// Variables that are not set keep the default RateLimitOptions, which don't limit votes.
+fn rate_limit_options(layers: &Layers) -> Result<RateLimitOptions, EnvError> {
+    let mut options = RateLimitOptions::default();
+
+    if let Some(value) = layers.load_parse::<f64>("VOTE_RATE_LIMIT", "a non-negative number")? {
+        if !value.is_finite() || value < 0.0 {
+            return Err(layers.invalid("VOTE_RATE_LIMIT", "a non-negative number"));
+        }
+
+        options.rate = value;
+    }
+
+    if let Some(value) = layers.load_parse::<f64>("VOTE_RATE_BURST", "a number of at least 1")? {
+        if !value.is_finite() || value < 1.0 {
+            return Err(layers.invalid("VOTE_RATE_BURST", "a number of at least 1"));
+        }
+
+        options.burst = value;
+    }
+
+    if let Some(value) = layers.load_parse::<bool>("VOTE_RATE_PER_NAME", "true or false")? {
+        options.per_name = value;
+    }
+
+    if let Some(value) = layers.load_parse::<u64>("VOTE_RATE_IDLE_SECONDS", "a number")? {
+        options.idle = Duration::from_secs(value);
+    }
+
+    if let Some(value) = layers.load_parse::<usize>("VOTE_RATE_MAX_ENTRIES", "a number")? {
+        options.max_entries = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables VOTE_DEDUPE_SECONDS and VOTE_DEDUPE_MAX_ENTRIES.
+///
+/// Variables that are not set keep the default DedupeOptions, which count every vote.
+fn dedupe_options(layers: &Layers) -> Result<DedupeOptions, EnvError> {
+    let mut options = DedupeOptions::default();
+
+    if let Some(value) = layers.load_parse::<f64>("VOTE_DEDUPE_SECONDS", "a non-negative number")? {
+        if !value.is_finite() || value < 0.0 {
+            return Err(layers.invalid("VOTE_DEDUPE_SECONDS", "a non-negative number"));
+        }
+
+        options.window = Duration::from_secs_f64(value);
+    }
+
+    if let Some(value) = layers.load_parse::<usize>("VOTE_DEDUPE_MAX_ENTRIES", "a number")? {
+        options.max_entries = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables API_KEYS and API_PUBLIC_READ.
+///
+/// API_KEYS is a comma separated list of key:role, with role read, vote or admin.
+/// If API_KEYS is not set, every request is allowed.
+fn auth_options(layers: &Layers) -> Result<AuthOptions, EnvError> {
+    let mut options = AuthOptions::default();
+    let expected: &str = "a comma separated list of key:role, with role read, vote or admin";
+
+    if let Ok(value) = layers.load_var("API_KEYS") {
+        let mut keys: HashMap<String, Role> = HashMap::new();
+
+        for pair in value
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+        {
+            let (key, role): (&str, Role) = match pair.rsplit_once(':') {
+                Some((key, role))
+                    if !key.is_empty() && key.bytes().all(|b| b.is_ascii_graphic()) =>
+                {
+                    match role.parse::<Role>() {
+                        Ok(role) => (key, role),
+                        Err(_) => return Err(layers.invalid("API_KEYS", expected)),
+                    }
+                }
+                _ => return Err(layers.invalid("API_KEYS", expected)),
+            };
+
+            keys.insert(key.into(), role);
+        }
+
+        options.keys = keys;
+    }
+
+    if let Some(value) = layers.load_parse::<bool>("API_PUBLIC_READ", "true or false")? {
+        options.public_read = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables CORS_ORIGINS, CORS_METHODS, CORS_HEADERS and CORS_MAX_AGE.
+///
+/// Each one except CORS_MAX_AGE is a comma separated list. If CORS_ORIGINS is not set, CORS is off.
+fn cors_options(layers: &Layers) -> Result<CorsOptions, EnvError> {
+    let mut options = CorsOptions::default();
+
+    let list = |key: &str| -> Option<Vec<String>> {
+        layers.load_var(key).ok().map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        })
+    };
+
+    if let Some(origins) = list("CORS_ORIGINS") {
+        if !origins.iter().all(|origin| is_valid_origin(origin)) {
+            return Err(layers.invalid(
+                "CORS_ORIGINS",
+                "a comma separated list of origins like https://example.com, or *",
+            ));
+        }
+
+        options.origins = origins;
+    }
+
+    if let Some(methods) = list("CORS_METHODS") {
+        options.methods = methods
+            .iter()
+            .map(|method| method.to_ascii_uppercase().parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| layers.invalid("CORS_METHODS", "a comma separated list of methods"))?;
+    }
+
+    if let Some(headers) = list("CORS_HEADERS") {
+        options.headers = headers
+            .iter()
+            .map(|header| header.parse())
+            .collect::<Result<_, _>>()
+            .map_err(|_| {
+                layers.invalid("CORS_HEADERS", "a comma separated list of header names")
+            })?;
+    }
+
+    if let Some(value) = layers.load_parse::<u64>("CORS_MAX_AGE", "a number of seconds")? {
+        options.max_age = value;
+    }
+
+    Ok(options)
+}
+
+/// Load the optional variables TLS_CERT_PATH and TLS_KEY_PATH.
+///
+/// Both must be set to serve HTTPS. If neither is set, the server uses plain HTTP.
+fn tls_options(layers: &Layers) -> Result<Option<TlsOptions>, EnvError> {
+    match (
+        layers.load_var("TLS_CERT_PATH"),
+        layers.load_var("TLS_KEY_PATH"),
+    ) {
+        (Ok(cert_path), Ok(key_path)) => Ok(Some(TlsOptions {
+            cert_path,
+            key_path,
+        })),
+        (Ok(_), Err(_)) => Err(EnvError::NotFound("TLS_KEY_PATH".into())),
+        (Err(_), Ok(_)) => Err(EnvError::NotFound("TLS_CERT_PATH".into())),
+        (Err(_), Err(_)) => Ok(None),
+    }
+}
+
+/// Load the optional variable TRUSTED_PROXIES, a comma separated list of IP addresses.
+fn trusted_proxies(layers: &Layers) -> Result<Vec<IpAddr>, EnvError> {
+    let value: String = match layers.load_var("TRUSTED_PROXIES") {
+        Ok(value) => value,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|address| !address.is_empty())
+        .map(|address| {
+            address.parse::<IpAddr>().map_err(|_| {
+                layers.invalid("TRUSTED_PROXIES", "a comma separated list of IP addresses")
+            })
+        })
+        .collect()
+}
+
+/// Load the dictionaries listed in DICTIONARIES, a comma separated list of names.
+///
+/// Each dictionary NAME reads its data file from DICTIONARY_NAME_PATH (default './name.json'),
+/// and its number of suggestions from DICTIONARY_NAME_SUGGESTION_NUMBER (default SUGGESTION_NUMBER).
+///
+/// DEFAULT_DICTIONARY names the dictionary used by the routes without a dictionary, default is the first one.
+///
+/// If DICTIONARIES is not set, there is a single dictionary called 'default' using DATA_PATH.
+fn load_dictionaries(
+    layers: &Layers,
+    suggestion_number: SuggestionNumber,
+    load_options: LoadOptions,
+) -> Result<(Vec<DictionaryConfig>, String), EnvError> {
+    let names: String = match layers.load_var("DICTIONARIES") {
+        Ok(value) => value,
+        Err(_) => {
+            let dictionary = DictionaryConfig {
+                name: DEFAULT_DICTIONARY.into(),
+                suggestion_number,
+                load_options,
+            };
+
+            return Ok((Vec::from([dictionary]), DEFAULT_DICTIONARY.into()));
+        }
+    };
+
+    let mut dictionaries: Vec<DictionaryConfig> = Vec::new();
+
+    for name in names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        if !is_valid_name(name) || dictionaries.iter().any(|d| d.name == name) {
+            return Err(layers.invalid(
+                "DICTIONARIES",
+                "a comma separated list of unique names with lowercase letters, digits, '-' or '_'",
+            ));
+        }
+
+        let key: String = dictionary_key(name);
+
+        let path: String = match layers.load_var(&format!("DICTIONARY_{key}_PATH")) {
+            Ok(value) => value,
+            Err(_) => format!("./{name}.json"),
+        };
+
+        let suggestions_key: String = format!("DICTIONARY_{key}_SUGGESTION_NUMBER");
+        let suggestions: SuggestionNumber =
+            match layers.load_parse::<SuggestionNumber>(&suggestions_key, "a number") {
+                Ok(value) => value.unwrap_or(suggestion_number),
+                Err(_) => return Err(EnvError::InvalidValueNumber(layers.name(&suggestions_key))),
+            };
+
+        dictionaries.push(DictionaryConfig {
+            name: name.into(),
+            suggestion_number: suggestions,
+            load_options: LoadOptions {
+                path,
+                ..load_options.clone()
+            },
+        });
+    }
+
+    let default: String = match layers.load_var("DEFAULT_DICTIONARY") {
+        Ok(value) => value,
+        Err(_) => match dictionaries.first() {
+            Some(value) => value.name.clone(),
+            None => return Err(EnvError::NotFound("DICTIONARIES".into())),
+        },
+    };
+
+    if !dictionaries.iter().any(|d| d.name == default) {
+        return Err(layers.invalid("DEFAULT_DICTIONARY", "one of the names in DICTIONARIES"));
+    }
+
+    Ok((dictionaries, default))
 }
 
 /// If the feature "with-dotenv" is enabled. Load local .env environment variables."
 #[cfg(feature = "dotenv")]
 fn load_locally() {
     match dotenv::dotenv() {
-        Err(err) => println!("Failed to load .env file. Error:{err}.\n\n Continuing..."),
+        Err(err) => eprintln!("Failed to load .env file. Error:{err}.\n\n Continuing..."),
         Ok(_) => {}
     }
 }
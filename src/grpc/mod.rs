@@ -0,0 +1,459 @@
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use tonic::{
+    metadata::MetadataMap,
+    transport::{server::TcpIncoming, Server},
+    Request, Response, Status,
+};
+
+use crate::{
+    auth::{request_key, Auth, Forbidden, Role, API_KEY},
+    dedupe::Voter,
+    entry::Entry,
+    limit::RateLimiter,
+    metrics::{DUPLICATE_VOTES, GET_ENTRIES_SECONDS, NOT_FOUND, RATE_LIMITED, VOTE_SECONDS},
+    procedures::{
+        find::tree::Find,
+        get::{tree::Get, TagFilter},
+        vote::{tree::Vote, VoteResult},
+    },
+    registry::{Dictionary, Registry},
+    tree::Tree,
+};
+
+/// Messages and service generated from `proto/typerecommendation.proto`.
+pub mod proto {
+    tonic::include_proto!("typerecommendation");
+}
+
+use proto::{
+    type_recommendation_server::{TypeRecommendation, TypeRecommendationServer},
+    BatchVoteRequest, BatchVoteResponse, GetEntryRequest, SuggestRequest, SuggestResponse,
+    VoteRequest, VoteResponse,
+};
+
+impl From<&Entry> for proto::Entry {
+    fn from(entry: &Entry) -> Self {
+        proto::Entry {
+            name: entry.get_name().into(),
+            times: *entry.get_times(),
+            tags: entry.get_tags().iter().cloned().collect(),
+            metadata: entry
+                .get_metadata()
+                .map(|metadata| {
+                    serde_json::to_string(metadata).expect("Metadata can be serialized.")
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// The gRPC service, answering with the same dictionaries, API keys and vote rate limit as the HTTP routes.
+///
+/// Can be cloned. All clones share the same dictionaries.
+#[derive(Clone)]
+pub struct GrpcService {
+    registry: Registry,
+    auth: Auth,
+    limiter: RateLimiter,
+}
+
+impl GrpcService {
+    pub fn new(registry: Registry, auth: Auth, limiter: RateLimiter) -> Self {
+        GrpcService {
+            registry,
+            auth,
+            limiter,
+        }
+    }
+
+    /// Returns Ok if the API key in metadata has role, see Auth::check.
+    fn authorize(&self, metadata: &MetadataMap, role: Role) -> Result<(), Status> {
+        let key: Option<&str> = request_key(
+            metadata.get(API_KEY).and_then(|value| value.to_str().ok()),
+            metadata
+                .get("authorization")
+                .and_then(|value| value.to_str().ok()),
+        );
+
+        self.auth
+            .check_key(key, role)
+            .map_err(|rejection| match rejection.find::<Forbidden>() {
+                Some(Forbidden(role)) => {
+                    Status::permission_denied(format!("The API key doesn't have the {role} role"))
+                }
+                None => Status::unauthenticated("Missing or unknown API key"),
+            })
+    }
+
+    /// Returns the dictionary with given name, or the default dictionary if name is empty.
+    ///
+    /// Like the /rec routes, fails until every dictionary is loaded.
+    fn dictionary(&self, name: &str) -> Result<Dictionary, Status> {
+        if self
+            .registry
+            .iter()
+            .any(|(_, dictionary)| !dictionary.reloader.is_ready())
+        {
+            return Err(Status::unavailable(
+                "The dictionaries are still loading, try again later",
+            ));
+        }
+
+        match name {
+            "" => Ok(self.registry.get_default().clone()),
+            name => match self.registry.get(name) {
+                Some(dictionary) => Ok(dictionary.clone()),
+                None => Err(Status::not_found(format!("Dictionary {name} not found"))),
+            },
+        }
+    }
+}
+
+/// Count a vote for name, like POST `/rec` does.
+///
+/// A repeated vote from the same client address and user, if there is one, within the dedupe window
+/// is answered with the current Entry, without counting it.
+fn count_vote(
+    dictionary: &Dictionary,
+    name: &str,
+    user: Option<&str>,
+    client: Option<IpAddr>,
+) -> VoteResponse {
+    let voter: Option<Voter> = client.map(|address| Voter::new(address, user));
+
+    if let Some(voter) = voter {
+        if dictionary.dedupe.is_duplicate(&voter, name) {
+            DUPLICATE_VOTES.inc();
+            return unapplied(dictionary, name);
+        }
+    }
+
+    let _timer = VOTE_SECONDS.start_timer();
+
+    match dictionary.current().vote(name) {
+        VoteResult::Success(entry) => {
+            if let Some(user) = user {
+                dictionary.overlay.record(user, entry.get_name());
+            }
+
+            VoteResponse {
+                entry: Some((&entry).into()),
+                applied: true,
+            }
+        }
+        VoteResult::NotFound => {
+            NOT_FOUND.with_label_values(&["vote"]).inc();
+
+            VoteResponse {
+                entry: None,
+                applied: false,
+            }
+        }
+    }
+}
+
+/// Response for a vote that was not counted, with the Entry of name as it is now.
+fn unapplied(dictionary: &Dictionary, name: &str) -> VoteResponse {
+    VoteResponse {
+        entry: dictionary.current().find(name).as_ref().map(Into::into),
+        applied: false,
+    }
+}
+
+#[tonic::async_trait]
+impl TypeRecommendation for GrpcService {
+    async fn suggest(
+        &self,
+        request: Request<SuggestRequest>,
+    ) -> Result<Response<SuggestResponse>, Status> {
+        self.authorize(request.metadata(), Role::Read)?;
+
+        let request: SuggestRequest = request.into_inner();
+        let dictionary: Dictionary = self.dictionary(&request.dictionary)?;
+
+        let _timer = GET_ENTRIES_SECONDS.start_timer();
+
+        let tree: Tree = dictionary.current();
+        let filter = TagFilter {
+            include: request.tags.into_iter().collect(),
+            exclude: request.exclude_tags.into_iter().collect(),
+        };
+
+        let values: Vec<Entry> = match tree.get_top_filtered(&request.prefix, &filter) {
+            Ok(values) => values,
+            Err(err) => {
+                tracing::debug!("{err}.");
+                NOT_FOUND.with_label_values(&["get"]).inc();
+                Vec::new()
+            }
+        };
+
+        let values: Vec<Entry> = match &request.user {
+            None => values,
+            Some(user) => dictionary
+                .overlay
+                .blend(user, &request.prefix, values, &tree, &filter),
+        };
+
+        Ok(Response::new(SuggestResponse {
+            suggestions: values.iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn vote(&self, request: Request<VoteRequest>) -> Result<Response<VoteResponse>, Status> {
+        self.authorize(request.metadata(), Role::Vote)?;
+
+        let client: Option<IpAddr> = request.remote_addr().map(|address| address.ip());
+        let request: VoteRequest = request.into_inner();
+        let dictionary: Dictionary = self.dictionary(&request.dictionary)?;
+
+        let address: IpAddr = client.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        if self.limiter.check(address, &request.name).is_err() {
+            tracing::debug!(client = %address, "Vote rate limited.");
+            RATE_LIMITED.inc();
+            return Err(Status::resource_exhausted(
+                "Too many votes, try again later",
+            ));
+        }
+
+        let response: VoteResponse =
+            count_vote(&dictionary, &request.name, request.user.as_deref(), client);
+
+        match response.entry {
+            None => Err(Status::not_found(format!(
+                "Name {} not found",
+                request.name
+            ))),
+            Some(_) => Ok(Response::new(response)),
+        }
+    }
+
+    async fn batch_vote(
+        &self,
+        request: Request<BatchVoteRequest>,
+    ) -> Result<Response<BatchVoteResponse>, Status> {
+        self.authorize(request.metadata(), Role::Vote)?;
+
+        let client: Option<IpAddr> = request.remote_addr().map(|address| address.ip());
+        let request: BatchVoteRequest = request.into_inner();
+        let dictionary: Dictionary = self.dictionary(&request.dictionary)?;
+
+        let address: IpAddr = client.unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        let results: Vec<VoteResponse> = request
+            .names
+            .iter()
+            .map(|name| match self.limiter.check(address, name) {
+                Ok(()) => count_vote(&dictionary, name, request.user.as_deref(), client),
+                Err(_) => {
+                    RATE_LIMITED.inc();
+                    unapplied(&dictionary, name)
+                }
+            })
+            .collect();
+
+        Ok(Response::new(BatchVoteResponse { results }))
+    }
+
+    async fn get_entry(
+        &self,
+        request: Request<GetEntryRequest>,
+    ) -> Result<Response<proto::Entry>, Status> {
+        self.authorize(request.metadata(), Role::Read)?;
+
+        let request: GetEntryRequest = request.into_inner();
+        let dictionary: Dictionary = self.dictionary(&request.dictionary)?;
+
+        match dictionary.current().find(&request.name) {
+            Some(entry) => Ok(Response::new((&entry).into())),
+            None => {
+                NOT_FOUND.with_label_values(&["get"]).inc();
+                Err(Status::not_found(format!(
+                    "Name {} not found",
+                    request.name
+                )))
+            }
+        }
+    }
+}
+
+/// Serve service on incoming connections until shutdown completes.
+pub async fn serve<F>(
+    service: GrpcService,
+    incoming: TcpIncoming,
+    shutdown: F,
+) -> Result<(), tonic::transport::Error>
+where
+    F: Future<Output = ()>,
+{
+    Server::builder()
+        .add_service(TypeRecommendationServer::new(service))
+        .serve_with_incoming_shutdown(incoming, shutdown)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use tokio::{net::TcpListener, sync::oneshot};
+    use tonic::{transport::Channel, Code};
+
+    use crate::{auth::AuthOptions, limit::RateLimitOptions, sample::sample_tree};
+
+    use super::proto::type_recommendation_client::TypeRecommendationClient;
+
+    async fn new_registry() -> Registry {
+        Registry::for_tests(Dictionary::for_tests(sample_tree(2).await))
+    }
+
+    /// Serve service on a free local port, and connect a client to it.
+    /// The server stops when the returned sender is dropped.
+    async fn start(
+        service: GrpcService,
+    ) -> (TypeRecommendationClient<Channel>, oneshot::Sender<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (stop, stopped) = oneshot::channel::<()>();
+
+        tokio::spawn(serve(service, TcpIncoming::from(listener), async move {
+            let _ = stopped.await;
+        }));
+
+        let client = TypeRecommendationClient::connect(format!("http://{address}"))
+            .await
+            .unwrap();
+
+        (client, stop)
+    }
+
+    fn names(entries: &[proto::Entry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn suggest_and_vote() {
+        let registry: Registry = new_registry().await;
+        let service = GrpcService::new(
+            registry,
+            Auth::new(AuthOptions::default()),
+            RateLimiter::new(RateLimitOptions::default()),
+        );
+        let (mut client, _stop) = start(service).await;
+
+        let suggestions = client
+            .suggest(SuggestRequest {
+                prefix: "an".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .suggestions;
+        assert_eq!(names(&suggestions), ["Anna", "Annabel"]);
+
+        let voted = client
+            .vote(VoteRequest {
+                name: "ann".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(voted.applied);
+        assert_eq!(voted.entry.unwrap().times, 11);
+
+        let status = client
+            .vote(VoteRequest {
+                name: "Nobody".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        let batch = client
+            .batch_vote(BatchVoteRequest {
+                names: vec!["Bob".into(), "Nobody".into(), "Bob".into()],
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .results;
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0].entry.as_ref().unwrap().times, 6);
+        assert!(!batch[1].applied && batch[1].entry.is_none());
+        assert_eq!(batch[2].entry.as_ref().unwrap().times, 7);
+
+        let entry = client
+            .get_entry(GetEntryRequest {
+                name: "BOB".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!((entry.name.as_str(), entry.times), ("Bob", 7));
+
+        let status = client
+            .suggest(SuggestRequest {
+                dictionary: "unknown".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn api_keys() {
+        let registry: Registry = new_registry().await;
+        let auth = Auth::new(AuthOptions {
+            keys: HashMap::from([("reader".to_string(), Role::Read)]),
+            public_read: false,
+        });
+        let service = GrpcService::new(
+            registry,
+            auth,
+            RateLimiter::new(RateLimitOptions::default()),
+        );
+        let (mut client, _stop) = start(service).await;
+
+        let status = client
+            .get_entry(GetEntryRequest {
+                name: "Bob".into(),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::Unauthenticated);
+
+        let mut request = Request::new(GetEntryRequest {
+            name: "Bob".into(),
+            ..Default::default()
+        });
+        request
+            .metadata_mut()
+            .insert(API_KEY, "reader".parse().unwrap());
+        assert_eq!(
+            client.get_entry(request).await.unwrap().into_inner().times,
+            5
+        );
+
+        let mut request = Request::new(VoteRequest {
+            name: "Bob".into(),
+            ..Default::default()
+        });
+        request
+            .metadata_mut()
+            .insert("authorization", "Bearer reader".parse().unwrap());
+        let status = client.vote(request).await.unwrap_err();
+        assert_eq!(status.code(), Code::PermissionDenied);
+    }
+}
@@ -0,0 +1,51 @@
+//! Prefix Tree of names ranked by votes, for typeahead recommendations.
+//!
+//! A [Tree] stores an [Entry] for each name, with the number of 'times' it was voted for.
+//! Its operations are traits implemented by Tree:
+//!
+//! - [Load]: Include entries, or stream them from a JSON object of names and times.
+//! - [Get]: Top entries for a prefix, ignoring capitalization.
+//! - [Vote]: Increment the 'times' of a name.
+//! - [Export]: Every entry in order of name, or written as JSON, JSON Lines or CSV.
+//!
+//! A Tree can be cloned and shared between threads. All clones point to the same entries.
+//!
+//! ```
+//! use typerecommendation::{Get, Load, Tree, Vote};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let tree: Tree = Tree::new_empty(2).await;
+//! for entry in [("Ann", 10), ("Anna", 30), ("Annabel", 20), ("Bob", 5)] {
+//!     tree.include(entry.into()).unwrap();
+//! }
+//!
+//! for _ in 0..15 {
+//!     tree.vote("Ann");
+//! }
+//!
+//! let names: Vec<String> = tree
+//!     .get_top("an")
+//!     .unwrap()
+//!     .iter()
+//!     .map(|entry| entry.get_name().to_string())
+//!     .collect();
+//! assert_eq!(names, ["Anna", "Ann"]);
+//! # }
+//! ```
+//!
+//! The other operations used by the server, like Find, SetMetadata and Stats, are in [procedures].
+
+pub mod entry;
+mod node;
+pub mod procedures;
+pub mod tree;
+
+pub use entry::Entry;
+pub use procedures::{
+    export::{tree::Export, ExportFormat},
+    get::{tree::Get, GetPrefixError, TagFilter},
+    load::{tree::Load, LoadError, LoadOptions, LoadSummary},
+    vote::{tree::Vote, VoteResult},
+};
+pub use tree::Tree;
@@ -0,0 +1,217 @@
+mod options;
+
+use std::{
+    collections::{HashMap, VecDeque},
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use warp::reject::Reject;
+
+pub use options::RateLimitOptions;
+
+/// Rejection for votes over the rate limit, with the time until the next vote is allowed.
+#[derive(Debug)]
+pub struct RateLimited(pub Duration);
+
+impl Reject for RateLimited {}
+
+/// Votes a client can make right now, refilled over time.
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+/// Client and, if the limit is per name, the name voted for.
+type Key = (IpAddr, Option<String>);
+
+struct Buckets {
+    buckets: HashMap<Key, Bucket>,
+    // Keys in the order their buckets were created, to forget the oldest when there are too many.
+    order: VecDeque<Key>,
+    last_sweep: Instant,
+}
+
+/// Token bucket rate limit for votes, by client address and optionally by name.
+///
+/// Each client starts with burst votes, and gets rate votes back each second up to burst.
+/// Clients that are idle for longer than idle are forgotten, a new bucket would be full anyway.
+/// At most max_entries buckets are kept, so a client voting for many names can't grow them without bound.
+///
+/// Can be cloned. All clones share the same buckets.
+#[derive(Clone)]
+pub struct RateLimiter {
+    options: RateLimitOptions,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    pub fn new(options: RateLimitOptions) -> Self {
+        let buckets = Buckets {
+            buckets: HashMap::new(),
+            order: VecDeque::new(),
+            last_sweep: Instant::now(),
+        };
+
+        RateLimiter {
+            options,
+            buckets: Arc::new(Mutex::new(buckets)),
+        }
+    }
+
+    /// Take a vote from the bucket of client and name.
+    ///
+    /// Returns the time until the next vote is allowed if the bucket is empty.
+    pub fn check(&self, client: IpAddr, name: &str) -> Result<(), Duration> {
+        self.check_at(client, name, Instant::now())
+    }
+
+    fn check_at(&self, client: IpAddr, name: &str, now: Instant) -> Result<(), Duration> {
+        let RateLimitOptions {
+            rate,
+            burst,
+            per_name,
+            idle,
+            max_entries,
+        } = self.options;
+
+        if rate <= 0.0 {
+            return Ok(());
+        }
+
+        let mut buckets = self.buckets.lock();
+        let Buckets {
+            buckets,
+            order,
+            last_sweep,
+        } = &mut *buckets;
+
+        // Sweeping is linear in the number of clients, but happens at most once every idle period.
+        if now.saturating_duration_since(*last_sweep) >= idle {
+            buckets.retain(|_, bucket| now.saturating_duration_since(bucket.last) < idle);
+            order.retain(|key| buckets.contains_key(key));
+            *last_sweep = now;
+        }
+
+        let key: Key = (client, per_name.then(|| name.to_ascii_lowercase()));
+
+        if !buckets.contains_key(&key) {
+            while buckets.len() >= max_entries {
+                match order.pop_front() {
+                    Some(oldest) => buckets.remove(&oldest),
+                    None => break,
+                };
+            }
+
+            order.push_back(key.clone());
+        }
+
+        let bucket: &mut Bucket = buckets.entry(key).or_insert(Bucket {
+            tokens: burst,
+            last: now,
+        });
+
+        let elapsed: f64 = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+
+    /// Number of clients, or clients and names, being tracked.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.buckets.lock().buckets.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(per_name: bool) -> RateLimiter {
+        RateLimiter::new(RateLimitOptions {
+            rate: 1.0,
+            burst: 2.0,
+            per_name,
+            idle: Duration::from_secs(60),
+            max_entries: 3,
+        })
+    }
+
+    #[test]
+    fn buckets_refill_and_evict() {
+        let limiter: RateLimiter = limiter(false);
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "203.0.113.8".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check_at(client, "Ann", start).is_ok());
+        assert!(limiter.check_at(client, "Bob", start).is_ok());
+
+        let wait: Duration = limiter.check_at(client, "Ann", start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        assert!(limiter.check_at(other, "Ann", start).is_ok());
+
+        let later: Instant = start + Duration::from_millis(1500);
+        assert!(limiter.check_at(client, "Ann", later).is_ok());
+        assert_eq!(
+            limiter.check_at(client, "Ann", later).unwrap_err(),
+            Duration::from_millis(500)
+        );
+
+        // other has been idle for longer than a minute, and is forgotten.
+        let idle: Instant = later + Duration::from_secs(60);
+        assert!(limiter.check_at(client, "Ann", idle).is_ok());
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn per_name() {
+        let limiter: RateLimiter = limiter(true);
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check_at(client, "Ann", start).is_ok());
+        assert!(limiter.check_at(client, "ann", start).is_ok());
+        assert!(limiter.check_at(client, "Ann", start).is_err());
+        assert!(limiter.check_at(client, "Bob", start).is_ok());
+    }
+
+    #[test]
+    fn max_entries() {
+        let limiter: RateLimiter = limiter(true);
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = Instant::now();
+
+        for i in 0..100 {
+            assert!(limiter.check_at(client, &format!("name{i}"), start).is_ok());
+        }
+
+        assert_eq!(limiter.len(), 3);
+
+        // The oldest bucket was forgotten, the newest are kept.
+        assert!(limiter.check_at(client, "name99", start).is_ok());
+        assert!(limiter.check_at(client, "name99", start).is_err());
+        assert!(limiter.check_at(client, "name0", start).is_ok());
+        assert_eq!(limiter.len(), 3);
+    }
+
+    #[test]
+    fn disabled() {
+        let limiter = RateLimiter::new(RateLimitOptions::default());
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+
+        for _ in 0..100 {
+            assert!(limiter.check(client, "Ann").is_ok());
+        }
+    }
+}
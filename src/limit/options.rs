@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// Settings of the vote rate limit.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitOptions {
+    /// Votes per second each client can keep up. 0 disables the limit.
+    pub rate: f64,
+    /// Votes a client can make at once, after being idle.
+    pub burst: f64,
+    /// If true, each client has a separate limit for each name.
+    pub per_name: bool,
+    /// Clients that haven't voted for this long are forgotten.
+    pub idle: Duration,
+    /// Clients, or clients and names, tracked at most. The oldest are forgotten first.
+    pub max_entries: usize,
+}
+
+impl Default for RateLimitOptions {
+    fn default() -> Self {
+        RateLimitOptions {
+            rate: 0.0,
+            burst: 10.0,
+            per_name: false,
+            idle: Duration::from_secs(300),
+            max_entries: 100_000,
+        }
+    }
+}
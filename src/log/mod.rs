@@ -0,0 +1,67 @@
+mod options;
+
+use std::io::IsTerminal;
+
+use tracing::{field::Empty, Span};
+use tracing_subscriber::EnvFilter;
+use warp::{
+    filters::trace::{Info as TraceInfo, Trace},
+    log::{Info, Log},
+};
+
+pub use options::{LogFormat, LogOptions};
+
+/// Start writing log events to stdout, filtered and formatted according to options.
+///
+/// # Panics
+///
+/// If options.filter is not valid, or if called more than once.
+pub fn init(options: &LogOptions) {
+    let filter = EnvFilter::try_new(&options.filter).expect("Invalid log filter.");
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_ansi(std::io::stdout().is_terminal());
+
+    match options.format {
+        LogFormat::Human => builder.init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .init(),
+    }
+}
+
+/// Wraps the routes in a "request" span, so every event of a request carries its fields.
+///
+/// The request ID and remote address are recorded by the server filters, and endpoints fill in
+/// prefix, name and results with Span::current().record, see finish.
+pub fn request_span() -> Trace<impl Fn(TraceInfo) -> Span + Clone> {
+    warp::trace(|info: TraceInfo| {
+        tracing::info_span!(
+            "request",
+            id = Empty,
+            remote = Empty,
+            method = %info.method(),
+            path = %info.path(),
+            prefix = Empty,
+            name = Empty,
+            results = Empty,
+            status = Empty,
+            latency_ms = Empty,
+        )
+    })
+}
+
+/// Log the end of each request with its status and latency, inside the request span.
+pub fn finish() -> Log<impl Fn(Info) + Copy> {
+    warp::log::custom(|info: Info| {
+        let span = Span::current();
+        let latency_ms: f64 = info.elapsed().as_secs_f64() * 1000.0;
+
+        span.record("status", info.status().as_u16());
+        span.record("latency_ms", latency_ms);
+
+        tracing::info!("Request finished.");
+    })
+}
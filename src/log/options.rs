@@ -0,0 +1,59 @@
+use std::{fmt::Display, str::FromStr};
+
+use tracing_subscriber::EnvFilter;
+
+/// How log lines are written.
+///
+/// - Human: One readable line per event, with the fields of its spans.
+/// - Json: One JSON object per event, for log collectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Human => write!(f, "human"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Which events are logged, and how.
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    /// Level and module filters, e.g. "info,typerecommendation::procedures=trace".
+    pub filter: String,
+    pub format: LogFormat,
+}
+
+impl LogOptions {
+    /// If filter is a valid list of directives.
+    pub fn is_valid_filter(filter: &str) -> bool {
+        EnvFilter::try_new(filter).is_ok()
+    }
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        LogOptions {
+            // warp logs the start and end of every request at info, which Request finished already covers
+            filter: "info,warp=warn".into(),
+            format: LogFormat::Human,
+        }
+    }
+}
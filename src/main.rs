@@ -1,35 +1,155 @@
+mod auth;
+mod dedupe;
 mod endpoints;
-mod entry;
 mod env;
+mod grpc;
+mod limit;
 mod log;
-mod node;
-mod procedures;
+mod metrics;
+mod personal;
+mod registry;
+mod reload;
+#[cfg(test)]
+#[path = "tree/sample.rs"]
+mod sample;
 mod server;
-mod tree;
 
-use std::net::{SocketAddr, ToSocketAddrs};
+use std::{
+    collections::HashMap,
+    net::{SocketAddr, ToSocketAddrs},
+};
+
+// The server modules use the library modules by these paths.
+use typerecommendation::{entry, procedures, tree};
 
 use crate::{
-    env::{EnvVars, SuggestionNumber},
-    tree::Tree,
+    auth::Auth,
+    dedupe::{Dedupe, DedupeOptions},
+    env::{Args, EnvVars, HELP},
+    limit::RateLimiter,
+    personal::{Overlay, PersonalOptions},
+    registry::{Dictionary, DictionaryConfig, Registry},
+    reload::{ReloadError, Reloader},
+    server::{AccessLog, ServerOptions, Tls},
 };
 
+/// Create an empty Tree for each dictionary, to be loaded once the server is listening.
+async fn new_dictionaries(
+    dictionaries: Vec<DictionaryConfig>,
+    keep_votes: bool,
+    personal: PersonalOptions,
+    dedupe: DedupeOptions,
+) -> HashMap<String, Dictionary> {
+    let mut pending: HashMap<String, Dictionary> = HashMap::new();
+
+    for dictionary in dictionaries {
+        let DictionaryConfig {
+            name,
+            suggestion_number,
+            load_options,
+        } = dictionary;
+
+        let reloader = Reloader::pending(suggestion_number, load_options, keep_votes)
+            .await
+            .with_gauges(&name);
+        let overlay = Overlay::new(personal);
+        let dedupe = Dedupe::new(dedupe);
+
+        pending.insert(
+            name,
+            Dictionary {
+                reloader,
+                overlay,
+                dedupe,
+            },
+        );
+    }
+
+    pending
+}
+
+/// Load the Tree of each dictionary in the background. Exits the process if any of them fails to load.
+fn load_dictionaries(registry: &Registry) {
+    for (name, dictionary) in registry.iter() {
+        let name: String = name.clone();
+        let reloader: Reloader = dictionary.reloader.clone();
+
+        tokio::spawn(async move {
+            match reloader.reload().await {
+                Ok(summary) => tracing::info!(dictionary = name, "{}", summary.load),
+                // A reload requested meanwhile is loading the same file.
+                Err(ReloadError::InProgress) => {}
+                Err(ReloadError::Load(err)) => {
+                    tracing::error!(dictionary = name, "Error Loading Tree. {}", err);
+                    std::process::exit(1);
+                }
+            }
+        });
+    }
+}
+
 #[tokio::main]
 async fn main() {
-    let env_vars = match EnvVars::new() {
+    let args = match Args::parse(std::env::args().skip(1)) {
         Ok(value) => value,
-        Err(err) => panic!("Environment Variables Error: {}", err),
+        Err(err) => {
+            eprintln!("Configuration Error: {err}");
+            std::process::exit(2);
+        }
     };
 
-    let host: String = env_vars.host;
-    let port: String = env_vars.port;
-    let suggestions: SuggestionNumber = env_vars.suggestion_number;
+    if args.help {
+        print!("{HELP}");
+        return;
+    }
+
+    // Logging is not set up yet, errors go to stderr.
+    let env_vars = match EnvVars::load(&args) {
+        Ok(value) => value,
+        Err(err) => {
+            eprintln!("Configuration Error: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    if args.print_config {
+        print!("{}", env_vars.print_config());
+        return;
+    }
 
-    let tree = match Tree::new(suggestions).await {
+    log::init(&env_vars.log);
+
+    let access_log: Option<AccessLog> = match AccessLog::open(&env_vars.access_log) {
         Ok(value) => value,
-        Err(err) => panic!("Error Loading Tree: {}", err),
+        Err(err) => {
+            tracing::error!("Failed to open the access log. {err}");
+            std::process::exit(1);
+        }
     };
 
+    let tls: Option<Tls> = match env_vars.tls.map(Tls::load).transpose() {
+        Ok(value) => value,
+        Err(err) => {
+            tracing::error!("Failed to load the TLS certificate. {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let host: String = env_vars.host;
+    let port: String = env_vars.port;
+
+    let dictionaries = new_dictionaries(
+        env_vars.dictionaries,
+        env_vars.keep_votes,
+        env_vars.personal,
+        env_vars.dedupe,
+    )
+    .await;
+    let registry = Registry::new(dictionaries, env_vars.default_dictionary);
+
+    // The server starts listening while the dictionaries load, see /readyz.
+    load_dictionaries(&registry);
+
     let host_port = format!("{host}:{port}");
     let default_socket: SocketAddr = format!("0.0.0.0:{port}")
         .parse()
@@ -39,9 +159,9 @@ async fn main() {
     let socket_addr: SocketAddr = match host_port.parse() {
         Ok(value) => value,
         Err(err) => {
-            println!("Failed to parse {host}:{port} as IPV4 or IPV6 address. err: {err} .");
-            println!("Warp and Rust standard libraries currently have no method of recognizing URI addresses.");
-            println!("Therefore utilizing an unindentified address instead 0.0.0.0 .");
+            tracing::warn!("Failed to parse {host}:{port} as IPV4 or IPV6 address. err: {err} .");
+            tracing::warn!("Warp and Rust standard libraries currently have no method of recognizing URI addresses.");
+            tracing::warn!("Therefore utilizing an unindentified address instead 0.0.0.0 .");
 
             match host_port.to_socket_addrs() {
                 Err(_) => default_socket,
@@ -53,5 +173,20 @@ async fn main() {
         }
     };
 
-    server::start(socket_addr, tree).await;
+    // gRPC listens on the same host as the HTTP server.
+    let grpc: Option<SocketAddr> = env_vars
+        .grpc_port
+        .map(|grpc_port| SocketAddr::new(socket_addr.ip(), grpc_port));
+
+    let options = ServerOptions {
+        access_log,
+        limiter: RateLimiter::new(env_vars.rate_limit),
+        trusted_proxies: env_vars.trusted_proxies,
+        auth: Auth::new(env_vars.auth),
+        cors: env_vars.cors,
+        tls,
+        grpc,
+    };
+
+    server::start(socket_addr, registry, options).await;
 }
@@ -0,0 +1,197 @@
+use std::sync::LazyLock;
+
+use prometheus::{
+    register_histogram, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Histogram, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+};
+use warp::{
+    http::Method,
+    log::{Info, Log},
+};
+
+// Metrics are registered once in the default prometheus registry, and shared by every request.
+
+/// Requests answered, by route and status code.
+pub static REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "typerecommendation_requests_total",
+        "Number of HTTP requests answered, by route and status code.",
+        &["method", "route", "status"]
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Time spent looking up the suggestions for a prefix.
+pub static GET_ENTRIES_SECONDS: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "typerecommendation_get_entries_duration_seconds",
+        "Time spent looking up the suggestions for a prefix.",
+        latency_buckets()
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Time spent applying a vote.
+pub static VOTE_SECONDS: LazyLock<Histogram> = LazyLock::new(|| {
+    register_histogram!(
+        "typerecommendation_vote_duration_seconds",
+        "Time spent applying a vote.",
+        latency_buckets()
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Prefixes and names that were not found, by procedure ("get" or "vote").
+pub static NOT_FOUND: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "typerecommendation_not_found_total",
+        "Number of prefixes (get) or names (vote) that were not found in the tree.",
+        &["procedure"]
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Votes rejected by the rate limit.
+pub static RATE_LIMITED: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "typerecommendation_votes_rate_limited_total",
+        "Number of votes rejected because the client was over the rate limit."
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Votes acknowledged without being counted, because the same client voted for the name recently.
+pub static DUPLICATE_VOTES: LazyLock<IntCounter> = LazyLock::new(|| {
+    register_int_counter!(
+        "typerecommendation_votes_duplicate_total",
+        "Number of votes not counted because the client voted for the same name within the dedupe window."
+    )
+    .expect("Failed to register metric.")
+});
+
+/// WebSocket keystroke sessions that are open.
+pub static SESSIONS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "typerecommendation_sessions",
+        "Number of open WebSocket keystroke sessions."
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Clients of the Server-Sent Events endpoints that are connected.
+pub static EVENT_STREAMS: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!(
+        "typerecommendation_event_streams",
+        "Number of connected Server-Sent Events clients."
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Names in each dictionary, updated when it is loaded or reloaded.
+pub static ENTRIES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "typerecommendation_entries",
+        "Number of names in the tree of each dictionary.",
+        &["dictionary"]
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Nodes in each dictionary, updated when it is loaded or reloaded.
+pub static NODES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "typerecommendation_nodes",
+        "Number of nodes in the tree of each dictionary.",
+        &["dictionary"]
+    )
+    .expect("Failed to register metric.")
+});
+
+/// Lookups take microseconds, so buckets start lower than the prometheus defaults.
+fn latency_buckets() -> Vec<f64> {
+    Vec::from([
+        0.000_01, 0.000_05, 0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0,
+    ])
+}
+
+/// Wraps the routes to count every request by route and status.
+pub fn track() -> Log<impl Fn(Info) + Copy> {
+    warp::log::custom(|info: Info| {
+        let route: &str = route_label(info.method(), info.path());
+
+        REQUESTS
+            .with_label_values(&[info.method().as_str(), route, info.status().as_str()])
+            .inc();
+    })
+}
+
+/// Route template of a request path, so names and prefixes don't become labels.
+///
+/// Paths that don't belong to any route are labeled "other".
+pub fn route_label(method: &Method, path: &str) -> &'static str {
+    let trailing_slash: bool = path.len() > 1 && path.ends_with('/');
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (segments.as_slice(), method == Method::GET) {
+        (["rec"], _) => "/rec",
+        (["rec", "ws"], true) => "/rec/ws",
+        (["rec", _, "ws"], true) => "/rec/{dictionary}/ws",
+        (["rec", "events"], true) => "/rec/events",
+        (["rec", _, "events"], true) => "/rec/{dictionary}/events",
+        (["rec", _], true) if trailing_slash => "/rec/{dictionary}/",
+        (["rec", _], true) => "/rec/{prefix}",
+        (["rec", _], false) => "/rec/{dictionary}",
+        (["rec", _, _], true) => "/rec/{dictionary}/{prefix}",
+        (["admin", "reload"], false) => "/admin/reload",
+        (["admin", "reload", _], false) => "/admin/reload/{dictionary}",
+        (["admin", "metadata"], false) => "/admin/metadata",
+        (["admin", "metadata", _], false) => "/admin/metadata/{dictionary}",
+        (["stats"], true) => "/stats",
+        (["stats", _], true) => "/stats/{dictionary}",
+        (["export"], true) => "/export",
+        (["export", _], true) => "/export/{dictionary}",
+        (["metrics"], true) => "/metrics",
+        _ => "other",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_labels() {
+        assert_eq!(route_label(&Method::GET, "/rec"), "/rec");
+        assert_eq!(route_label(&Method::GET, "/rec/"), "/rec");
+        assert_eq!(route_label(&Method::GET, "/rec/amb"), "/rec/{prefix}");
+        assert_eq!(
+            route_label(&Method::GET, "/rec/cities/"),
+            "/rec/{dictionary}/"
+        );
+        assert_eq!(
+            route_label(&Method::GET, "/rec/cities/ams"),
+            "/rec/{dictionary}/{prefix}"
+        );
+        assert_eq!(route_label(&Method::GET, "/rec/ws"), "/rec/ws");
+        assert_eq!(
+            route_label(&Method::GET, "/rec/cities/ws"),
+            "/rec/{dictionary}/ws"
+        );
+        assert_eq!(route_label(&Method::GET, "/rec/events"), "/rec/events");
+        assert_eq!(route_label(&Method::POST, "/rec"), "/rec");
+        assert_eq!(
+            route_label(&Method::POST, "/rec/cities"),
+            "/rec/{dictionary}"
+        );
+        assert_eq!(
+            route_label(&Method::POST, "/admin/reload/cities"),
+            "/admin/reload/{dictionary}"
+        );
+        assert_eq!(
+            route_label(&Method::GET, "/export/cities"),
+            "/export/{dictionary}"
+        );
+        assert_eq!(route_label(&Method::GET, "/admin/reload"), "other");
+        assert_eq!(route_label(&Method::GET, "/favicon.ico"), "other");
+    }
+}
@@ -21,6 +21,9 @@ pub trait NodeExt {
     /// Retrieving entry is the same as retrieving prefix and times together.
     fn get_entry(&self) -> &Entry;
 
+    /// Mutable Reference to the entry. The name should not be changed.
+    fn get_entry_mut(&mut self) -> &mut Entry;
+
     /// Atomic pointer to parent Node.
     fn get_parent(&self) -> Option<Weak<RwLock<Node>>>;
 
@@ -7,8 +7,10 @@ mod ext;
 
 use crate::{
     entry::Entry,
-    log::log_debug,
-    procedures::{get::node::Get, load::node::Load, vote::node::Vote},
+    procedures::{
+        export::node::Export, find::node::Find, get::node::Get, load::node::Load,
+        metadata::node::SetMetadata, stats::node::Stats, vote::node::Vote,
+    },
 };
 
 pub use crate::node::ext::NodeExt;
@@ -36,8 +38,16 @@ pub struct Node {
     suggestions: usize,
 }
 
+impl Export for Node {}
+
+impl Find for Node {}
+
 impl Load for Node {}
 
+impl SetMetadata for Node {}
+
+impl Stats for Node {}
+
 impl Get for Node {}
 
 impl Vote for Node {}
@@ -69,7 +79,7 @@ impl Node {
 #[cfg(not(test))]
 impl Drop for Node {
     fn drop(&mut self) {
-        log_debug(&format!("Shutting down Node {} .", self.entry.get_name()))
+        tracing::trace!(node = self.entry.get_name(), "Dropping node.");
     }
 }
 
@@ -94,15 +104,11 @@ impl NodeExt for Node {
         &self.entry
     }
 
-    fn get_parent(&self) -> Option<Weak<RwLock<Node>>> {
-        log_debug("\nFunction crate::node::NodeExt::get_parent...\n");
-        log_debug(&format!(
-            "Node prefix: {}, times: {} is_none: {} .",
-            &self.entry.get_name(),
-            self.entry.get_times(),
-            self.parent.is_none()
-        ));
+    fn get_entry_mut(&mut self) -> &mut Entry {
+        &mut self.entry
+    }
 
+    fn get_parent(&self) -> Option<Weak<RwLock<Node>>> {
         self.parent.clone()
     }
 
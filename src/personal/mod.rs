@@ -0,0 +1,266 @@
+mod options;
+
+use std::{cmp::Ordering, collections::HashMap, sync::Arc};
+
+use parking_lot::Mutex;
+
+use crate::{
+    entry::Entry,
+    procedures::{find::tree::Find, get::TagFilter},
+    tree::Tree,
+};
+
+pub use options::PersonalOptions;
+
+/// Names a single user voted for, and how many times.
+struct UserVotes {
+    // Value of Users::clock when this user was last seen, used for evicting the least recently seen user
+    last_seen: u64,
+    names: HashMap<String, u64>,
+}
+
+struct Users {
+    clock: u64,
+    users: HashMap<String, UserVotes>,
+}
+
+/// Per-user vote counts, merged with the global ranking when that user asks for suggestions.
+///
+/// Memory is bounded by PersonalOptions: users are evicted least recently seen first,
+/// and each user keeps a limited number of names.
+///
+/// Can be cloned. All clones share the same users.
+#[derive(Clone)]
+pub struct Overlay {
+    options: PersonalOptions,
+    users: Arc<Mutex<Users>>,
+}
+
+impl Overlay {
+    pub fn new(options: PersonalOptions) -> Self {
+        let users = Users {
+            clock: 0,
+            users: HashMap::new(),
+        };
+
+        Overlay {
+            options,
+            users: Arc::new(Mutex::new(users)),
+        }
+    }
+
+    /// Count a vote from user for name. name should be the name stored in the Tree.
+    pub fn record(&self, user: &str, name: &str) {
+        if self.options.max_users == 0 || self.options.max_names == 0 {
+            return;
+        }
+
+        let mut users = self.users.lock();
+        users.clock += 1;
+        let clock: u64 = users.clock;
+
+        if !users.users.contains_key(user) && users.users.len() >= self.options.max_users {
+            // Scanning is linear in max_users, but only happens when a new user arrives to a full overlay.
+            let oldest: Option<String> = users
+                .users
+                .iter()
+                .min_by_key(|(_, votes)| votes.last_seen)
+                .map(|(user, _)| user.clone());
+
+            if let Some(oldest) = oldest {
+                users.users.remove(&oldest);
+            }
+        }
+
+        let votes: &mut UserVotes = users.users.entry(user.into()).or_insert(UserVotes {
+            last_seen: clock,
+            names: HashMap::new(),
+        });
+        votes.last_seen = clock;
+
+        if !votes.names.contains_key(name) && votes.names.len() >= self.options.max_names {
+            let least: Option<String> = votes
+                .names
+                .iter()
+                .min_by_key(|(_, times)| **times)
+                .map(|(name, _)| name.clone());
+
+            if let Some(least) = least {
+                votes.names.remove(&least);
+            }
+        }
+
+        let times: &mut u64 = votes.names.entry(name.into()).or_default();
+        *times = times.saturating_add(1);
+    }
+
+    /// Names user voted for that start with prefix, ignoring capitalization. Marks user as recently seen.
+    fn names(&self, user: &str, prefix: &str) -> Vec<(String, u64)> {
+        let mut users = self.users.lock();
+        users.clock += 1;
+        let clock: u64 = users.clock;
+
+        let votes: &mut UserVotes = match users.users.get_mut(user) {
+            None => return Vec::new(),
+            Some(value) => value,
+        };
+        votes.last_seen = clock;
+
+        votes
+            .names
+            .iter()
+            .filter(|(name, _)| {
+                name.len() >= prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+            })
+            .map(|(name, times)| (name.clone(), *times))
+            .collect()
+    }
+
+    /// Re-rank the global top entries for prefix with the personal votes of user.
+    ///
+    /// Each Entry is ranked by 'times' plus weight times the personal votes for that name.
+    /// Names the user voted for that are not in entries are looked up in tree, and must match filter.
+    /// The Entry named exactly as prefix stays first, like in the global ranking.
+    pub fn blend(
+        &self,
+        user: &str,
+        prefix: &str,
+        entries: Vec<Entry>,
+        tree: &Tree,
+        filter: &TagFilter,
+    ) -> Vec<Entry> {
+        let personal: Vec<(String, u64)> = self.names(user, prefix);
+
+        if personal.is_empty() {
+            return entries;
+        }
+
+        let limit: usize = tree.get_suggestions().max(entries.len());
+        let personal_times = |entry: &Entry| -> u64 {
+            personal
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(entry.get_name()))
+                .map(|(_, times)| *times)
+                .unwrap_or(0)
+        };
+
+        let mut candidates: Vec<Entry> = entries;
+        for (name, _) in &personal {
+            let included: bool = candidates
+                .iter()
+                .any(|entry| entry.get_name().eq_ignore_ascii_case(name));
+
+            if !included {
+                // Names removed from the Tree after a reload are ignored.
+                if let Some(entry) = tree.find(name).filter(|entry| filter.matches(entry)) {
+                    candidates.push(entry);
+                }
+            }
+        }
+
+        let exact: Option<usize> = candidates
+            .iter()
+            .position(|entry| entry.get_name().eq_ignore_ascii_case(prefix));
+        let first: Option<Entry> = exact.map(|index| candidates.remove(index));
+
+        let score = |entry: &Entry| -> f64 {
+            *entry.get_times() as f64 + self.options.weight * personal_times(entry) as f64
+        };
+
+        candidates.sort_by(|a, b| {
+            score(b)
+                .partial_cmp(&score(a))
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| b.cmp(a))
+        });
+
+        let mut result: Vec<Entry> = Vec::with_capacity(limit);
+        result.extend(first);
+        result.extend(candidates);
+        result.truncate(limit);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::procedures::{get::tree::Get, load::tree::Load};
+
+    async fn new_tree() -> Tree {
+        let tree: Tree = Tree::new_empty(3).await;
+
+        for entry in [("Ann", 100), ("Anna", 50), ("Annabel", 40), ("Annie", 10)] {
+            tree.include(entry.into()).unwrap();
+        }
+
+        tree
+    }
+
+    fn names(entries: &[Entry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.get_name()).collect()
+    }
+
+    fn top(tree: &Tree, prefix: &str) -> Vec<Entry> {
+        tree.get_top(prefix).unwrap()
+    }
+
+    #[tokio::test]
+    async fn blend_includes_personal_names() {
+        let tree: Tree = new_tree().await;
+        let options = PersonalOptions {
+            weight: 10.0,
+            ..PersonalOptions::default()
+        };
+        let overlay = Overlay::new(options);
+
+        // Annie is not in the global top 3 for "ann"
+        assert_eq!(names(&top(&tree, "ann")), ["Ann", "Anna", "Annabel"]);
+
+        for _ in 0..5 {
+            overlay.record("alice", "Annie");
+        }
+
+        let filter = TagFilter::default();
+        let blended = overlay.blend("alice", "ann", top(&tree, "ann"), &tree, &filter);
+        assert_eq!(names(&blended), ["Ann", "Annie", "Anna"]);
+
+        // Other users are not affected
+        let blended = overlay.blend("bob", "ann", top(&tree, "ann"), &tree, &filter);
+        assert_eq!(names(&blended), ["Ann", "Anna", "Annabel"]);
+    }
+
+    #[test]
+    fn least_recently_seen_user_is_evicted() {
+        let options = PersonalOptions {
+            weight: 1.0,
+            max_users: 2,
+            max_names: 2,
+        };
+        let overlay = Overlay::new(options);
+
+        overlay.record("alice", "Ann");
+        overlay.record("bob", "Ann");
+        overlay.names("alice", "");
+        overlay.record("carol", "Ann");
+
+        assert_eq!(overlay.names("alice", "").len(), 1);
+        assert_eq!(overlay.names("bob", "").len(), 0);
+        assert_eq!(overlay.names("carol", "").len(), 1);
+
+        overlay.record("alice", "Ann");
+        overlay.record("alice", "Bob");
+        overlay.record("alice", "Carl");
+
+        let mut remembered: Vec<String> = overlay
+            .names("alice", "")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        remembered.sort();
+
+        assert_eq!(remembered, ["Ann", "Carl"]);
+    }
+}
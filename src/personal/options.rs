@@ -0,0 +1,20 @@
+/// Settings of the per-user overlay.
+#[derive(Debug, Clone, Copy)]
+pub struct PersonalOptions {
+    /// Each personal vote adds this much to the global 'times' of a name when ranking for that user.
+    pub weight: f64,
+    /// Number of users remembered. The least recently seen user is forgotten first.
+    pub max_users: usize,
+    /// Number of names remembered per user. The least voted name is forgotten first.
+    pub max_names: usize,
+}
+
+impl Default for PersonalOptions {
+    fn default() -> Self {
+        PersonalOptions {
+            weight: 100.0,
+            max_users: 10_000,
+            max_names: 100,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Weak;
+
+use parking_lot::RwLock;
+
+use crate::{
+    entry::Entry,
+    node::{Node, NodeExt},
+    procedures::export::node::Export,
+};
+
+/// Iterator over every Entry of a Tree, in order of their lowercase names.
+///
+/// Created by Export::entries. Only one Node is locked at a time, and only while it is visited,
+/// so requests keep running during the iteration. Entries included or voted for meanwhile
+/// may be returned as they were before or after the change.
+pub struct Entries {
+    // Nodes to visit, the next one last.
+    pending: Vec<Weak<RwLock<Node>>>,
+}
+
+impl Entries {
+    pub(crate) fn new(first: Weak<RwLock<Node>>) -> Self {
+        Entries {
+            pending: Vec::from([first]),
+        }
+    }
+}
+
+impl Iterator for Entries {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(lock) = self.pending.pop() {
+            // A Node dropped during the iteration is simply skipped.
+            let Some(node) = lock.upgrade() else {
+                continue;
+            };
+
+            let node = node.read();
+            self.pending.extend(node.export_child_locks());
+
+            if node.is_name() {
+                return Some(node.get_entry().clone());
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,247 @@
+use std::{fmt::Display, io::Write, str::FromStr};
+
+use serde_json::{Map, Value};
+
+use crate::{entry::Entry, tree::Counter};
+
+/// How exported entries are written.
+///
+/// - Json: A JSON object of names and records, the same format the data file is loaded from.
+///   Names without tags or metadata have the number of times as their record.
+/// - Jsonl: One JSON Entry per line, with name, times, tags and metadata.
+/// - Csv: Columns name, times, tags and metadata. Tags are separated by ';' and metadata is JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Jsonl,
+    Csv,
+}
+
+impl ExportFormat {
+    /// Media type of the exported entries.
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Jsonl => "application/jsonl",
+            Self::Csv => "text/csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "csv" => Ok(Self::Csv),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Jsonl => write!(f, "jsonl"),
+            Self::Csv => write!(f, "csv"),
+        }
+    }
+}
+
+/// Writes entries one at a time in an ExportFormat.
+///
+/// The opening of the format is written on creation and the closing on finish,
+/// so entries can be written as they are read from the Tree.
+pub struct ExportWriter<W: Write> {
+    format: ExportFormat,
+    out: W,
+    written: Counter,
+}
+
+impl<W: Write> ExportWriter<W> {
+    /// Start writing entries to out.
+    pub fn new(format: ExportFormat, mut out: W) -> std::io::Result<Self> {
+        match format {
+            ExportFormat::Json => out.write_all(b"{")?,
+            ExportFormat::Jsonl => {}
+            ExportFormat::Csv => out.write_all(b"name,times,tags,metadata\n")?,
+        }
+
+        Ok(ExportWriter {
+            format,
+            out,
+            written: 0,
+        })
+    }
+
+    /// Write a single Entry.
+    pub fn write(&mut self, entry: &Entry) -> std::io::Result<()> {
+        match self.format {
+            ExportFormat::Json => {
+                let separator: &[u8] = if self.written == 0 { b"\n" } else { b",\n" };
+                self.out.write_all(separator)?;
+                serde_json::to_writer(&mut self.out, entry.get_name())?;
+                self.out.write_all(b": ")?;
+                serde_json::to_writer(&mut self.out, &record(entry))?;
+            }
+            ExportFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, entry)?;
+                self.out.write_all(b"\n")?;
+            }
+            ExportFormat::Csv => {
+                let tags: Vec<&str> = entry.get_tags().iter().map(String::as_str).collect();
+                let metadata: String = match entry.get_metadata() {
+                    None => String::new(),
+                    Some(metadata) => serde_json::to_string(metadata)?,
+                };
+
+                writeln!(
+                    self.out,
+                    "{},{},{},{}",
+                    csv_field(entry.get_name()),
+                    entry.get_times(),
+                    csv_field(&tags.join(";")),
+                    csv_field(&metadata),
+                )?;
+            }
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of entries written so far.
+    pub fn written(&self) -> Counter {
+        self.written
+    }
+
+    /// Mutable reference to the output, e.g. to take the bytes written to a buffer so far.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    /// Write the closing of the format and return the output.
+    pub fn finish(mut self) -> std::io::Result<W> {
+        if self.format == ExportFormat::Json {
+            let closing: &[u8] = if self.written == 0 { b"}\n" } else { b"\n}\n" };
+            self.out.write_all(closing)?;
+        }
+
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// The record of an Entry in the data file. Only times, unless it has tags or metadata.
+fn record(entry: &Entry) -> Value {
+    if entry.get_tags().is_empty() && entry.get_metadata().is_none() {
+        return Value::from(*entry.get_times());
+    }
+
+    let mut record: Map<String, Value> = Map::new();
+    record.insert("times".into(), Value::from(*entry.get_times()));
+
+    if !entry.get_tags().is_empty() {
+        record.insert("tags".into(), entry.get_tags().iter().cloned().collect());
+    }
+
+    if let Some(metadata) = entry.get_metadata() {
+        record.insert("metadata".into(), Value::Object(metadata.clone()));
+    }
+
+    Value::Object(record)
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn entries() -> Vec<Entry> {
+        let metadata = json!({ "id": 7, "note": "likes \"quotes\", commas" });
+
+        Vec::from([
+            Entry::new("Ann".into(), 10),
+            Entry::new("Bob".into(), 5)
+                .with_tags(["engineering".into(), "sales".into()].into())
+                .with_metadata(metadata.as_object().cloned()),
+        ])
+    }
+
+    fn export(format: ExportFormat, entries: &[Entry]) -> String {
+        let mut writer = ExportWriter::new(format, Vec::new()).unwrap();
+
+        for entry in entries {
+            writer.write(entry).unwrap();
+        }
+
+        assert_eq!(writer.written(), entries.len());
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn json_is_a_data_file() {
+        let exported: String = export(ExportFormat::Json, &entries());
+
+        let parsed: Value = serde_json::from_str(&exported).unwrap();
+        assert_eq!(
+            parsed,
+            json!({
+                "Ann": 10,
+                "Bob": {
+                    "times": 5,
+                    "tags": ["engineering", "sales"],
+                    "metadata": { "id": 7, "note": "likes \"quotes\", commas" }
+                }
+            })
+        );
+
+        assert_eq!(export(ExportFormat::Json, &[]), "{}\n");
+    }
+
+    #[test]
+    fn jsonl() {
+        let exported: String = export(ExportFormat::Jsonl, &entries());
+        let lines: Vec<Entry> = exported
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(lines, entries());
+        assert_eq!(lines[1].get_tags().len(), 2);
+    }
+
+    #[test]
+    fn csv() {
+        let exported: String = export(ExportFormat::Csv, &entries());
+
+        assert_eq!(
+            exported,
+            "name,times,tags,metadata\n\
+             Ann,10,,\n\
+             Bob,5,engineering;sales,\"{\"\"id\"\":7,\"\"note\"\":\"\"likes \\\"\"quotes\\\"\", commas\"\"}\"\n"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("JSON".parse(), Ok(ExportFormat::Json));
+        assert_eq!("ndjson".parse(), Ok(ExportFormat::Jsonl));
+        assert_eq!("csv".parse(), Ok(ExportFormat::Csv));
+        assert_eq!("xml".parse::<ExportFormat>(), Err(()));
+    }
+}
@@ -0,0 +1,7 @@
+mod entries;
+mod format;
+pub(crate) mod node;
+pub mod tree;
+
+pub use entries::Entries;
+pub use format::{ExportFormat, ExportWriter};
@@ -0,0 +1,24 @@
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::node::NodeExt;
+
+/// Contains all Node logic for exporting the Tree.
+pub trait Export {
+    /// Weak pointers to every child of this Node, in reverse order of their characters.
+    ///
+    /// Pushed onto a stack, the children are visited in order of their characters.
+    fn export_child_locks(&self) -> Vec<Weak<RwLock<Self>>>
+    where
+        Self: NodeExt + Sized,
+    {
+        let mut children: Vec<(&String, &Arc<RwLock<Self>>)> = self.get_children().iter().collect();
+        children.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+
+        children
+            .into_iter()
+            .map(|(_, child)| Arc::downgrade(child))
+            .collect()
+    }
+}
@@ -0,0 +1,52 @@
+use std::{io::Write, sync::Arc};
+
+use crate::{
+    procedures::export::{Entries, ExportFormat, ExportWriter},
+    tree::{Counter, TreeExt},
+};
+
+/// Contains all Tree logic for exporting every Entry.
+pub trait Export {
+    /// Iterate over every Entry, in order of their lowercase names.
+    ///
+    /// ```
+    /// use typerecommendation::{Export, Load, Tree};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let tree: Tree = Tree::new_empty(10).await;
+    /// for entry in [("Bob", 5), ("Ann", 10), ("Anna", 30)] {
+    ///     tree.include(entry.into()).unwrap();
+    /// }
+    ///
+    /// let names: Vec<String> = tree
+    ///     .entries()
+    ///     .map(|entry| entry.get_name().to_string())
+    ///     .collect();
+    /// assert_eq!(names, ["Ann", "Anna", "Bob"]);
+    /// # }
+    /// ```
+    fn entries(&self) -> Entries
+    where
+        Self: TreeExt,
+    {
+        Entries::new(Arc::downgrade(self.get_node()))
+    }
+
+    /// Write every Entry to out in given format. Returns the number of entries written.
+    fn export<W: Write>(&self, format: ExportFormat, out: W) -> std::io::Result<Counter>
+    where
+        Self: TreeExt,
+    {
+        let mut writer: ExportWriter<W> = ExportWriter::new(format, out)?;
+
+        for entry in self.entries() {
+            writer.write(&entry)?;
+        }
+
+        let written: Counter = writer.written();
+        writer.finish()?;
+
+        Ok(written)
+    }
+}
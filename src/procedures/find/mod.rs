@@ -0,0 +1,5 @@
+pub(crate) mod node;
+mod result;
+pub mod tree;
+
+pub(crate) use result::FindResult;
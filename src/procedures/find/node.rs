@@ -0,0 +1,33 @@
+use crate::{node::NodeExt, procedures::find::FindResult, tree::Counter};
+
+/// Contains all Node logic for looking up the Entry of a given name.
+pub trait Find {
+    /// Checks if current Node is valid for given name.
+    ///
+    /// If not valid, returns next Node to access, does not create new nodes.
+    ///
+    /// If valid, returns a copy of the Entry of this Node.
+    ///
+    /// If there are no remaining Nodes to access, or the Node is not a name, returns FindResult::NotFound.
+    fn find(&self, name: &str, counter: &mut Counter) -> FindResult
+    where
+        Self: NodeExt,
+    {
+        if *counter == name.len() {
+            return match self.is_name() {
+                true => FindResult::Found(self.get_entry().clone()),
+                false => FindResult::NotFound,
+            };
+        }
+
+        let character: &str = &name[*counter..(*counter + 1)];
+
+        match self.next_child(character) {
+            None => FindResult::NotFound,
+            Some(lock) => {
+                *counter += 1;
+                FindResult::Next(lock)
+            }
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use std::sync::Weak;
+
+use parking_lot::RwLock;
+
+use crate::{entry::Entry, node::Node};
+
+/// Result of looking up a name in the Prefix Tree.
+///
+/// - Next: Returned by a Node when the search is incomplete. Contains the next Node to access.
+/// - Found: Entry with the exact name.
+/// - NotFound: Name was not found on Prefix Tree.
+pub enum FindResult {
+    Next(Weak<RwLock<Node>>),
+    Found(Entry),
+    NotFound,
+}
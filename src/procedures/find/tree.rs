@@ -0,0 +1,40 @@
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::{
+    entry::Entry,
+    node::Node,
+    procedures::find::{node::Find as NodeFind, FindResult},
+    tree::{Counter, TreeExt},
+};
+
+/// Contains all Tree logic for looking up the Entry of a given name.
+pub trait Find {
+    /// Returns the Entry with given name, ignoring capitalization. Doesn't change the Tree.
+    fn find(&self, name: &str) -> Option<Entry>
+    where
+        Self: TreeExt,
+    {
+        // Nodes are indexed one byte at a time, a non-ASCII name can't be in the Tree.
+        if !name.is_ascii() {
+            return None;
+        }
+
+        let mut counter: Counter = 0;
+        let mut next: Weak<RwLock<Node>> = Arc::downgrade(self.get_node());
+
+        loop {
+            next = match next
+                .upgrade()
+                .expect("Tried to unlock Node but got None .")
+                .read()
+                .find(name, &mut counter)
+            {
+                FindResult::Next(lock) => lock,
+                FindResult::Found(entry) => return Some(entry),
+                FindResult::NotFound => return None,
+            };
+        }
+    }
+}
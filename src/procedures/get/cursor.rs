@@ -0,0 +1,175 @@
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::{
+    entry::Entry,
+    node::{Node, NodeExt},
+    procedures::get::{node::Get, Found, GetPrefixError, TagFilter},
+    tree::{Tree, TreeExt},
+};
+
+/// Position of a typing session in a Tree.
+///
+/// Keeps the deepest Node whose prefix starts the text, so each keystroke only moves from there:
+/// appending a character descends one level, deleting one goes back to the parent.
+pub struct Cursor {
+    // The Tree the node belongs to. Holding it keeps the node alive until the cursor moves to a reloaded Tree.
+    tree: Tree,
+    node: Weak<RwLock<Node>>,
+    // Length of the prefix of node, which is at most the length of text.
+    depth: usize,
+    text: String,
+}
+
+impl Cursor {
+    /// Cursor at the first Node of tree, for an empty text.
+    pub fn new(tree: Tree) -> Self {
+        let node: Weak<RwLock<Node>> = Arc::downgrade(tree.get_node());
+
+        Cursor {
+            tree,
+            node,
+            depth: 0,
+            text: String::new(),
+        }
+    }
+
+    /// Move to the Node for text in tree, starting from the current Node.
+    ///
+    /// Starts from the first Node if tree is not the Tree of the cursor, after a reload.
+    /// Returns the number of Nodes moved through.
+    pub fn move_to(&mut self, tree: &Tree, text: &str) -> usize {
+        if !Arc::ptr_eq(self.tree.get_node(), tree.get_node()) {
+            *self = Cursor::new(tree.clone());
+        }
+
+        let mut node: Arc<RwLock<Node>> = self
+            .node
+            .upgrade()
+            .expect("Error in move_to. The cursor holds its Tree, upgrading resulted in a None.");
+        let mut steps: usize = 0;
+
+        // Names are ASCII, so a shared byte is a shared character.
+        let shared: usize = self
+            .text
+            .bytes()
+            .zip(text.bytes())
+            .take_while(|(a, b)| a.eq_ignore_ascii_case(b))
+            .count();
+
+        while self.depth > shared {
+            let parent: Weak<RwLock<Node>> = node
+                .read()
+                .get_parent()
+                .expect("Only the first Node has no parent, and it has depth 0.");
+
+            node = parent
+                .upgrade()
+                .expect("Error in move_to. Upgrading the parent resulted in a None.");
+            self.depth -= 1;
+            steps += 1;
+        }
+
+        while self.depth < text.len() && text.as_bytes()[self.depth].is_ascii() {
+            let child: Option<Weak<RwLock<Node>>> =
+                node.read().next_child(&text[self.depth..(self.depth + 1)]);
+
+            match child.and_then(|child| child.upgrade()) {
+                None => break,
+                Some(child) => {
+                    node = child;
+                    self.depth += 1;
+                    steps += 1;
+                }
+            }
+        }
+
+        self.node = Arc::downgrade(&node);
+        self.text = text.into();
+
+        steps
+    }
+
+    /// Top recommendations for the text, among the entries matching filter.
+    ///
+    /// The Node of the text is reached one character per level, so its whole text matched.
+    pub fn top(&self, filter: &TagFilter) -> Result<Found, GetPrefixError> {
+        if self.depth < self.text.len() {
+            return Err(GetPrefixError::NotFound(self.text.clone()));
+        }
+
+        let node: Arc<RwLock<Node>> = self
+            .node
+            .upgrade()
+            .expect("Error in top. The cursor holds its Tree, upgrading resulted in a None.");
+
+        let entries: Vec<Entry> = node.read().top_entries(filter);
+
+        Ok(Found {
+            entries,
+            matched: self.depth,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{
+        procedures::{get::tree::Get as TreeGet, load::tree::Load},
+        tree::sample_tree,
+    };
+
+    fn names(entries: Vec<Entry>) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| entry.get_name().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn keystrokes_move_one_level() {
+        let tree: Tree = sample_tree(3).await;
+        let filter = TagFilter::default();
+        let mut cursor = Cursor::new(tree.clone());
+
+        assert_eq!(cursor.move_to(&tree, "A"), 1);
+        assert_eq!(cursor.move_to(&tree, "An"), 1);
+        assert_eq!(cursor.move_to(&tree, "Ann"), 1);
+        assert_eq!(
+            names(cursor.top(&filter).unwrap().entries),
+            ["Ann", "Anna", "Annabel"]
+        );
+
+        // Backspace
+        assert_eq!(cursor.move_to(&tree, "An"), 1);
+        assert_eq!(cursor.top(&filter).unwrap().matched, 2);
+
+        // The same results as a search from the first Node.
+        let expected: Vec<Entry> = tree.get_top("an").unwrap();
+        assert_eq!(names(cursor.top(&filter).unwrap().entries), names(expected));
+
+        // Replacing the text only goes back to the shared prefix.
+        assert_eq!(cursor.move_to(&tree, "Anx"), 0);
+        assert!(cursor.top(&filter).is_err());
+        assert_eq!(cursor.move_to(&tree, "Anxy"), 0);
+        assert_eq!(cursor.move_to(&tree, "Bob"), 5);
+        assert_eq!(names(cursor.top(&filter).unwrap().entries), ["Bob"]);
+    }
+
+    #[tokio::test]
+    async fn reloaded_tree_starts_over() {
+        let tree: Tree = sample_tree(3).await;
+        let filter = TagFilter::default();
+        let mut cursor = Cursor::new(tree.clone());
+        cursor.move_to(&tree, "ann");
+
+        let reloaded: Tree = Tree::new_empty(3).await;
+        reloaded.include(("Annie", 1).into()).unwrap();
+
+        assert_eq!(cursor.move_to(&reloaded, "anni"), 4);
+        assert_eq!(names(cursor.top(&filter).unwrap().entries), ["Annie"]);
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::BTreeSet;
+
+use crate::entry::Entry;
+
+/// Restricts which entries can be recommended, according to their tags.
+///
+/// An Entry matches if it has every tag in include, and none of the tags in exclude.
+/// The default filter matches every Entry.
+#[derive(Debug, Clone, Default)]
+pub struct TagFilter {
+    pub include: BTreeSet<String>,
+    pub exclude: BTreeSet<String>,
+}
+
+impl TagFilter {
+    /// Build a filter from comma separated lists of tags. Empty tags are ignored.
+    pub fn parse(include: Option<&str>, exclude: Option<&str>) -> Self {
+        let split = |tags: Option<&str>| -> BTreeSet<String> {
+            tags.unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(String::from)
+                .collect()
+        };
+
+        TagFilter {
+            include: split(include),
+            exclude: split(exclude),
+        }
+    }
+
+    /// If true, entry can be recommended.
+    pub fn matches(&self, entry: &Entry) -> bool {
+        let tags: &BTreeSet<String> = entry.get_tags();
+
+        self.include.is_subset(tags) && self.exclude.is_disjoint(tags)
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entry::Entry;
+
+/// Unit of the offsets in a Span.
+///
+/// - Utf16: UTF-16 code units, what JavaScript strings use.
+/// - Char: Unicode scalar values.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OffsetUnit {
+    Utf16,
+    Char,
+}
+
+/// Range of a name that matched the search, from start (inclusive) to end (exclusive).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// An Entry along with the ranges of its name that matched the search.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Highlighted {
+    #[serde(flatten)]
+    pub entry: Entry,
+    pub highlight: Vec<Span>,
+}
+
+/// Ranges of name matched by a prefix search that matched its first 'matched' characters.
+///
+/// The prefix search matches the start of the name, so there is at most one range.
+/// A search for the empty prefix matches no characters, and has none.
+pub fn prefix_spans(name: &str, matched: usize, unit: OffsetUnit) -> Vec<Span> {
+    let end: usize = name
+        .chars()
+        .take(matched)
+        .map(|character| match unit {
+            OffsetUnit::Utf16 => character.len_utf16(),
+            OffsetUnit::Char => 1,
+        })
+        .sum();
+
+    match end {
+        0 => Vec::new(),
+        end => Vec::from([Span { start: 0, end }]),
+    }
+}
+
+/// Attach the range matched by a prefix search to each Entry.
+///
+/// matched is the number of characters the search matched, as reported in Found.
+pub fn highlight_prefix(matched: usize, entries: Vec<Entry>, unit: OffsetUnit) -> Vec<Highlighted> {
+    entries
+        .into_iter()
+        .map(|entry| Highlighted {
+            highlight: prefix_spans(entry.get_name(), matched, unit),
+            entry,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_spans_cover_matched_characters() {
+        let spans: Vec<Span> = prefix_spans("Amber Wall", 3, OffsetUnit::Char);
+        assert_eq!(spans, [Span { start: 0, end: 3 }]);
+
+        assert!(prefix_spans("Amber Wall", 0, OffsetUnit::Char).is_empty());
+    }
+
+    #[test]
+    fn prefix_spans_units() {
+        // U+1D49C takes two UTF-16 code units
+        let name: &str = "\u{1D49C}my";
+
+        let spans: Vec<Span> = prefix_spans(name, 2, OffsetUnit::Utf16);
+        assert_eq!(spans, [Span { start: 0, end: 3 }]);
+
+        let spans: Vec<Span> = prefix_spans(name, 2, OffsetUnit::Char);
+        assert_eq!(spans, [Span { start: 0, end: 2 }]);
+    }
+
+    #[test]
+    fn highlighted_entries_serialize_flat() {
+        let entries: Vec<Entry> = Vec::from([("Amber", 3).into(), ("amy", 1).into()]);
+        let values: Vec<Highlighted> = highlight_prefix(2, entries, OffsetUnit::Utf16);
+
+        assert_eq!(
+            serde_json::to_value(&values).unwrap(),
+            serde_json::json!([
+                { "name": "Amber", "times": 3, "highlight": [{ "start": 0, "end": 2 }] },
+                { "name": "amy", "times": 1, "highlight": [{ "start": 0, "end": 2 }] }
+            ])
+        );
+    }
+}
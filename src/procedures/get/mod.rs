@@ -1,7 +1,14 @@
+mod cursor;
 mod error;
-pub mod node;
+mod filter;
+mod highlight;
+pub(crate) mod node;
 mod result;
 pub mod tree;
 
+pub use cursor::Cursor;
 pub use error::GetPrefixError;
-pub use result::SearchResult;
+pub use filter::TagFilter;
+pub use highlight::{highlight_prefix, Highlighted, OffsetUnit};
+pub use result::Found;
+pub(crate) use result::SearchResult;
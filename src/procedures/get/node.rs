@@ -2,11 +2,9 @@ use parking_lot::RwLock;
 
 use crate::{
     entry::Entry,
-    env::SuggestionNumber,
-    log::log_debug,
     node::NodeExt,
-    procedures::get::{GetPrefixError, SearchResult},
-    tree::Counter,
+    procedures::get::{Found, GetPrefixError, SearchResult, TagFilter},
+    tree::{Counter, SuggestionNumber},
 };
 
 use std::{collections::HashMap, sync::Arc};
@@ -17,10 +15,11 @@ pub trait Get {
     ///
     /// If not valid, return next Node to check.
     ///
-    /// If valid, check all children for the top recommendations.
+    /// If valid, check all children for the top recommendations matching filter.
     fn get_top_entries(
         &self,
         prefix: &str,
+        filter: &TagFilter,
         counter: &mut Counter,
     ) -> Result<SearchResult, GetPrefixError>
     where
@@ -29,39 +28,21 @@ pub trait Get {
         // If this is the node for the given prefix
         let is_correct_node: bool = prefix.len() == self.get_prefix().len();
 
-        log_debug(&format!(
-            "\nFunction crate::procedures::get::node::Get::get_top_entries ... Prefix: {}, Times: {}.\n",
-            self.get_prefix(),
-            self.get_times(),
-        ));
+        tracing::trace!(
+            node = self.get_prefix(),
+            times = self.get_times(),
+            "Visiting node."
+        );
 
         if is_correct_node {
-            log_debug("\nIs Correct Node\n");
-            log_debug(&format!(
-                "Returning result for prefix {} .",
-                self.get_prefix()
-            ));
-
-            let mut top: Vec<Entry> = Vec::with_capacity(self.get_suggestions());
-            let mut result = Vec::with_capacity(self.get_suggestions());
-
-            let mut suggestion_number = self.get_suggestions();
-
-            if self.is_name() {
-                let entry = self.get_entry().clone();
-                log_debug(&format!("Including first entry: {entry}"));
-                result.push(entry);
-                suggestion_number -= 1;
-            }
-            
-            self.collect_top_first(&mut top, &suggestion_number);
-
-            result.append(&mut top);
+            let result: Vec<Entry> = self.top_entries(filter);
 
-            let entries: usize = result.len();
-            log_debug(&format!("Returning {entries} entries."));
+            tracing::trace!(entries = result.len(), "Prefix found.");
 
-            return Ok(SearchResult::Success(result));
+            return Ok(SearchResult::Success(Found {
+                entries: result,
+                matched: self.get_prefix().chars().count(),
+            }));
         }
 
         *counter += 1;
@@ -74,31 +55,66 @@ pub trait Get {
         }
     }
 
+    /// Top recommendations matching filter among this Node and its descendants.
+    ///
+    /// This Node comes first if it is a name, since it is the exact match for its prefix.
+    fn top_entries(&self, filter: &TagFilter) -> Vec<Entry>
+    where
+        Self: NodeExt,
+    {
+        let mut top: Vec<Entry> = Vec::with_capacity(self.get_suggestions());
+        let mut result = Vec::with_capacity(self.get_suggestions());
+
+        let mut suggestion_number = self.get_suggestions();
+
+        if self.is_name() && filter.matches(self.get_entry()) {
+            let entry = self.get_entry().clone();
+            result.push(entry);
+            suggestion_number -= 1;
+        }
+
+        self.collect_top_first(&mut top, &suggestion_number, filter);
+
+        result.append(&mut top);
+
+        result
+    }
+
     // The first entry is ignored, because it is already included as the first recommendation.
 
     /// Recursively checks all children of current Node for recommendations.
-    fn collect_top_first(&self, top: &mut Vec<Entry>, suggestion_number: &SuggestionNumber)
-    where
+    fn collect_top_first(
+        &self,
+        top: &mut Vec<Entry>,
+        suggestion_number: &SuggestionNumber,
+        filter: &TagFilter,
+    ) where
         Self: NodeExt,
     {
         let children: &HashMap<String, Arc<RwLock<Self>>> = self.get_children();
 
         for (_, child) in children.iter() {
-            child.read().collect_top(top, suggestion_number);
+            child.read().collect_top(top, suggestion_number, filter);
         }
     }
 
-    fn collect_top(&self, top: &mut Vec<Entry>, suggestion_number: &SuggestionNumber)
-    where
+    // Entries that don't match filter are never included, so the top list only has matching entries.
+
+    fn collect_top(
+        &self,
+        top: &mut Vec<Entry>,
+        suggestion_number: &SuggestionNumber,
+        filter: &TagFilter,
+    ) where
         Self: NodeExt,
     {
         let children: &HashMap<String, Arc<RwLock<Self>>> = self.get_children();
 
         for (_, child) in children.iter() {
-            child.read().collect_top(top, suggestion_number);
+            child.read().collect_top(top, suggestion_number, filter);
         }
 
-        if self.is_name() {
+        if self.is_name() && filter.matches(self.get_entry()) {
             // If list is not full, include the entry.
             if top.len() < *suggestion_number {
                 let entry: Entry = self.get_entry().clone();
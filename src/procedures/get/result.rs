@@ -6,6 +6,15 @@ use crate::{entry::Entry, node::Node};
 
 /// Result for retrieving top recommendations from a given prefix.
 pub enum SearchResult {
-    Success(Vec<Entry>),
+    Success(Found),
     Next(Weak<RwLock<Node>>),
 }
+
+/// Top recommendations of the Node for a prefix.
+///
+/// Every name under that Node starts with its prefix,
+/// so the search matched the first 'matched' characters of each name.
+pub struct Found {
+    pub entries: Vec<Entry>,
+    pub matched: usize,
+}
@@ -3,21 +3,64 @@ use std::sync::{Arc, Weak};
 use parking_lot::RwLock;
 
 use crate::{
-    endpoints::rec::prefix::get::Output as GetNamesOutput,
-    log::log_debug,
+    entry::Entry,
     node::Node,
-    procedures::get::{node::Get as NodeGet, GetPrefixError, SearchResult},
+    procedures::get::{node::Get as NodeGet, Found, GetPrefixError, SearchResult, TagFilter},
     tree::TreeExt,
 };
 
 /// Contains all Tree logic for GET entry endpoint.
 pub trait Get {
-    /// Retrieves top recommendations for given prefix.
-    fn get_top(&self, prefix: &str) -> Result<GetNamesOutput, GetPrefixError>
+    /// Retrieves top recommendations for given prefix, ignoring capitalization.
+    ///
+    /// The Entry named exactly as prefix comes first, then the others by 'times'.
+    ///
+    /// ```
+    /// use typerecommendation::{Get, Load, Tree};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let tree: Tree = Tree::new_empty(2).await;
+    /// for entry in [("Ann", 10), ("Anna", 30), ("Annabel", 20)] {
+    ///     tree.include(entry.into()).unwrap();
+    /// }
+    ///
+    /// let top: Vec<String> = tree
+    ///     .get_top("ann")
+    ///     .unwrap()
+    ///     .iter()
+    ///     .map(|entry| entry.get_name().to_string())
+    ///     .collect();
+    /// assert_eq!(top, ["Ann", "Anna"]);
+    ///
+    /// assert!(tree.get_top("bob").is_err());
+    /// # }
+    /// ```
+    fn get_top(&self, prefix: &str) -> Result<Vec<Entry>, GetPrefixError>
+    where
+        Self: TreeExt,
+    {
+        self.get_top_filtered(prefix, &TagFilter::default())
+    }
+
+    /// Retrieves top recommendations for given prefix, among the entries matching filter.
+    fn get_top_filtered(
+        &self,
+        prefix: &str,
+        filter: &TagFilter,
+    ) -> Result<Vec<Entry>, GetPrefixError>
+    where
+        Self: TreeExt,
+    {
+        self.search(prefix, filter).map(|found| found.entries)
+    }
+
+    /// Retrieves top recommendations for given prefix, among the entries matching filter,
+    /// along with how many characters of their names the prefix matched.
+    fn search(&self, prefix: &str, filter: &TagFilter) -> Result<Found, GetPrefixError>
     where
         Self: TreeExt,
     {
-        log_debug("------------------------");
         let mut counter = 0;
         let first_node: Weak<RwLock<Node>> = Arc::downgrade(self.get_node());
 
@@ -25,19 +68,18 @@ pub trait Get {
 
         // Repeat until the last node
         loop {
-            log_debug("------------------------");
             traveller = match traveller
                 .upgrade()
                 .expect("Error in get_top_prefix. Upgrading Arc pointer resulted in a None.")
                 .read()
-                .get_top_entries(prefix, &mut counter)
+                .get_top_entries(prefix, filter, &mut counter)
             {
                 Err(err) => {
                     return Err(err);
                 }
                 Ok(search_result) => match search_result {
                     SearchResult::Next(pointer) => pointer,
-                    SearchResult::Success(value) => return Ok(SearchResult::Success(value).into()),
+                    SearchResult::Success(found) => return Ok(found),
                 },
             };
         }
@@ -6,6 +6,19 @@ use crate::{entry::Entry, tree::Counter};
 pub enum LoadError {
     EntryNameIsEmpty(Entry, Counter),
     ReferenceEmptyDuringLoad(Entry, Counter),
+    // Path of the file and the error returned while reading it
+    Io(String, std::io::Error),
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    // Name of the record and the value that couldn't be used as a count
+    InvalidCount(String, String),
+    // Name of the record and why it was rejected
+    InvalidName(String, &'static str),
+    // Name of the record and which of its properties is invalid
+    InvalidRecord(String, &'static str),
 }
 
 impl Display for LoadError {
@@ -13,6 +26,11 @@ impl Display for LoadError {
         match self{
             Self::EntryNameIsEmpty(entry, counter) => write!(f, "Loading JSON Error: Entry has an empty name. Counter: {}, Entry: {} .", counter, entry),
             Self::ReferenceEmptyDuringLoad(entry, counter) => write!(f, "Loading JSON Error: Loaded a node with a non-existent self-reference. Counter: {}, Entry: {} .", counter, entry),
+            Self::Io(path, err) => write!(f, "Loading JSON Error: Failed to read {}. Err: {} .", path, err),
+            Self::Parse { line, column, message } => write!(f, "Loading JSON Error: Invalid JSON at line {}, column {}. Err: {} .", line, column, message),
+            Self::InvalidCount(name, value) => write!(f, "Loading JSON Error: Invalid count for name {:?}. Expected a non-negative integer, got {} .", name, value),
+            Self::InvalidName(name, reason) => write!(f, "Loading JSON Error: Invalid name {:?}, {} .", name, reason),
+            Self::InvalidRecord(name, reason) => write!(f, "Loading JSON Error: Invalid record for name {:?}, {} .", name, reason),
         }
     }
 }
@@ -0,0 +1,331 @@
+use std::{collections::BTreeSet, fmt, io::Read};
+
+use serde::de::{Deserializer, Error, MapAccess, Visitor};
+use serde_json::Value;
+
+use crate::{
+    entry::{Entry, Metadata},
+    procedures::load::{BadRecordPolicy, LoadError, LoadSummary},
+};
+
+/// Number of entries deserialized before they are included in the Tree.
+pub const BATCH_SIZE: usize = 1024;
+
+// Reading the whole file into a String and then into a HashMap keeps two copies of the dataset
+// in memory before the first Node is created. Instead, the JSON object is walked one
+// key/value pair at a time, and entries are handed over in batches of a fixed size.
+//
+// Each value is either the number of times, or an object with the properties of the Entry:
+//
+// { "Ann": 10, "Bob": { "times": 5, "tags": ["engineering"], "metadata": { "id": 7 } } }
+
+/// Visits a JSON object of `"name": record` pairs, passing batches of entries to `on_batch`.
+struct EntryVisitor<'a, F> {
+    batch_size: usize,
+    bad_records: BadRecordPolicy,
+    source: &'a str,
+    on_batch: F,
+
+    // Errors returned by on_batch can't go through serde, so they are stored here instead.
+    error: &'a mut Option<LoadError>,
+}
+
+impl<'de, 'a, F> Visitor<'de> for EntryVisitor<'a, F>
+where
+    F: FnMut(&mut Vec<Entry>) -> Result<(), LoadError>,
+{
+    type Value = LoadSummary;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a JSON object of names and the times they were selected"
+        )
+    }
+
+    fn visit_map<A>(mut self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut batch: Vec<Entry> = Vec::with_capacity(self.batch_size);
+        let mut summary: LoadSummary = LoadSummary::empty(self.source);
+
+        while let Some((name, value)) = map.next_entry::<String, Value>()? {
+            match parse_entry(name, value) {
+                Ok(entry) => batch.push(entry),
+                Err(err) => match self.bad_records {
+                    BadRecordPolicy::Skip => {
+                        tracing::warn!(source = self.source, "Skipping record. {err}");
+                        summary.skipped += 1;
+                    }
+                    BadRecordPolicy::Abort => return Err(self.fail(err)),
+                },
+            }
+
+            if batch.len() >= self.batch_size {
+                summary.loaded += batch.len();
+                self.flush(&mut batch)?;
+
+                tracing::debug!(
+                    source = self.source,
+                    loaded = summary.loaded,
+                    "Loaded batch."
+                );
+            }
+        }
+
+        summary.loaded += batch.len();
+        self.flush(&mut batch)?;
+
+        Ok(summary)
+    }
+}
+
+impl<'a, F> EntryVisitor<'a, F>
+where
+    F: FnMut(&mut Vec<Entry>) -> Result<(), LoadError>,
+{
+    /// Hand the current batch over to on_batch and leave it empty for the next one.
+    fn flush<E: Error>(&mut self, batch: &mut Vec<Entry>) -> Result<(), E> {
+        if let Err(err) = (self.on_batch)(batch) {
+            return Err(self.fail(err));
+        }
+
+        batch.clear();
+        Ok(())
+    }
+
+    /// Store err so it can be returned after serde stops, and return a serde error to stop it.
+    fn fail<E: Error>(&mut self, err: LoadError) -> E {
+        let message = err.to_string();
+        *self.error = Some(err);
+
+        E::custom(message)
+    }
+}
+
+/// Validate a single `"name": record` pair.
+fn parse_entry(name: String, value: Value) -> Result<Entry, LoadError> {
+    if name.is_empty() {
+        return Err(LoadError::InvalidName(name, "name is empty"));
+    }
+
+    // Nodes are indexed one byte at a time.
+    if !name.is_ascii() {
+        return Err(LoadError::InvalidName(
+            name,
+            "name contains non-ASCII characters",
+        ));
+    }
+
+    let mut record = match value {
+        Value::Object(record) => record,
+        times => {
+            return match times.as_u64() {
+                Some(times) => Ok(Entry::new(name, times)),
+                None => Err(LoadError::InvalidCount(name, times.to_string())),
+            }
+        }
+    };
+
+    let times: u64 = match record.remove("times") {
+        None => return Err(LoadError::InvalidRecord(name, "times is missing")),
+        Some(times) => match times.as_u64() {
+            Some(times) => times,
+            None => return Err(LoadError::InvalidCount(name, times.to_string())),
+        },
+    };
+
+    let tags: BTreeSet<String> = match record.remove("tags") {
+        None | Some(Value::Null) => BTreeSet::new(),
+        Some(Value::Array(tags)) => {
+            let mut parsed: BTreeSet<String> = BTreeSet::new();
+
+            for tag in tags {
+                match tag {
+                    Value::String(tag) if !tag.is_empty() => parsed.insert(tag),
+                    _ => {
+                        return Err(LoadError::InvalidRecord(
+                            name,
+                            "tags must be an array of non-empty strings",
+                        ))
+                    }
+                };
+            }
+
+            parsed
+        }
+        Some(_) => {
+            return Err(LoadError::InvalidRecord(
+                name,
+                "tags must be an array of non-empty strings",
+            ))
+        }
+    };
+
+    let metadata: Option<Metadata> = match record.remove("metadata") {
+        None | Some(Value::Null) => None,
+        Some(Value::Object(metadata)) => Some(metadata),
+        Some(_) => {
+            return Err(LoadError::InvalidRecord(
+                name,
+                "metadata must be a JSON object",
+            ))
+        }
+    };
+
+    Ok(Entry::new(name, times)
+        .with_tags(tags)
+        .with_metadata(metadata))
+}
+
+/// Deserialize a JSON object of `"name": record` pairs from reader without buffering it.
+///
+/// Entries are passed to on_batch in groups of at most batch_size.
+/// Invalid records are skipped or abort the load depending on bad_records.
+///
+/// # Errors
+///
+/// Returns Err(LoadError) if the JSON is malformed, reading fails, a record is invalid with
+/// BadRecordPolicy::Abort, or on_batch fails.
+pub fn stream_json<R, F>(
+    reader: R,
+    source: &str,
+    batch_size: usize,
+    bad_records: BadRecordPolicy,
+    on_batch: F,
+) -> Result<LoadSummary, LoadError>
+where
+    R: Read,
+    F: FnMut(&mut Vec<Entry>) -> Result<(), LoadError>,
+{
+    let mut error: Option<LoadError> = None;
+
+    let visitor = EntryVisitor {
+        batch_size: batch_size.max(1),
+        bad_records,
+        source,
+        on_batch,
+        error: &mut error,
+    };
+
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let result = deserializer
+        .deserialize_map(visitor)
+        .and_then(|summary| deserializer.end().map(|_| summary));
+
+    if let Some(err) = error {
+        return Err(err);
+    }
+
+    let summary: LoadSummary = match result {
+        Ok(value) => value,
+        Err(err) if err.is_io() => {
+            let err: std::io::Error = err.into();
+            return Err(LoadError::Io(source.into(), err));
+        }
+        Err(err) => {
+            return Err(LoadError::Parse {
+                line: err.line(),
+                column: err.column(),
+                message: err.to_string(),
+            })
+        }
+    };
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(json: &str, bad_records: BadRecordPolicy) -> Result<LoadSummary, LoadError> {
+        stream_json(json.as_bytes(), "test", 2, bad_records, |batch| {
+            batch.clear();
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn skip_invalid_records() {
+        let json = r#"{ "Ann": 10, "": 3, "Bob": -1, "Carl": "many", "Dora": 2.5, "Eve": 1 }"#;
+
+        let summary: LoadSummary = stream(json, BadRecordPolicy::Skip).unwrap();
+
+        assert_eq!(summary.loaded, 2);
+        assert_eq!(summary.skipped, 4);
+    }
+
+    #[test]
+    fn abort_on_invalid_count() {
+        let json = r#"{ "Ann": 10, "Bob": -1, "Eve": 1 }"#;
+
+        match stream(json, BadRecordPolicy::Abort) {
+            Err(LoadError::InvalidCount(name, value)) => {
+                assert_eq!(name, "Bob");
+                assert_eq!(value, "-1");
+            }
+            other => panic!("Expected InvalidCount, got {:?} .", other),
+        }
+    }
+
+    #[test]
+    fn abort_on_invalid_name() {
+        let json = r#"{ "Ann": 10, "Zoë": 3 }"#;
+
+        match stream(json, BadRecordPolicy::Abort) {
+            Err(LoadError::InvalidName(name, _)) => assert_eq!(name, "Zoë"),
+            other => panic!("Expected InvalidName, got {:?} .", other),
+        }
+    }
+
+    #[test]
+    fn records_with_tags_and_metadata() {
+        let json = r#"{
+            "Ann": { "times": 10, "tags": ["engineering", "active"] },
+            "Bob": { "times": 5, "metadata": { "id": 7, "avatar": "bob.png" } },
+            "Carl": { "tags": ["sales"] },
+            "Dora": { "times": 2, "tags": "sales" },
+            "Eve": { "times": 2, "metadata": [1, 2] }
+        }"#;
+
+        let mut entries: Vec<Entry> = Vec::new();
+        let summary = stream_json(
+            json.as_bytes(),
+            "test",
+            10,
+            BadRecordPolicy::Skip,
+            |batch| {
+                entries.append(batch);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(summary.loaded, 2);
+        assert_eq!(summary.skipped, 3);
+
+        let tags: Vec<&str> = entries[0].get_tags().iter().map(String::as_str).collect();
+        assert_eq!(tags, ["active", "engineering"]);
+        assert!(entries[0].get_metadata().is_none());
+
+        assert!(entries[1].get_tags().is_empty());
+        let metadata = entries[1].get_metadata().expect("Bob has metadata");
+        assert_eq!(metadata["id"], 7);
+        assert_eq!(metadata["avatar"], "bob.png");
+    }
+
+    #[test]
+    fn parse_error_position() {
+        let json = "{\n  \"Ann\": 10,\n  \"Bob\" 3\n}";
+
+        match stream(json, BadRecordPolicy::Skip) {
+            Err(LoadError::Parse { line, column, .. }) => {
+                assert_eq!(line, 3);
+                assert_eq!(column, 9);
+            }
+            other => panic!("Expected Parse, got {:?} .", other),
+        }
+    }
+}
@@ -1,5 +1,10 @@
 mod error;
-pub mod node;
+mod json;
+pub(crate) mod node;
+mod options;
+mod summary;
 pub mod tree;
 
 pub use error::LoadError;
+pub use options::{BadRecordPolicy, LoadOptions};
+pub use summary::LoadSummary;
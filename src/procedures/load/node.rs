@@ -1,4 +1,4 @@
-use std::sync::Weak;
+use std::sync::{atomic::Ordering, Weak};
 
 use parking_lot::RwLock;
 
@@ -6,7 +6,7 @@ use crate::{
     entry::Entry,
     node::{Node, NodeExt},
     procedures::load::LoadError,
-    tree::Counter,
+    tree::{Counter, Size},
 };
 
 /// Contains all Node logic for loading entries into the prefix tree.
@@ -15,7 +15,9 @@ pub trait Load {
     ///
     /// If not valid, returns next Node to access, creates new Nodes as needed.
     ///
-    /// If valid, assign 'entry.times' to this Node.
+    /// If valid, assign 'entry.times', 'entry.tags' and 'entry.metadata' to this Node.
+    ///
+    /// Nodes created and names added or removed are counted in size.
     ///
     /// # Errors
     ///
@@ -24,6 +26,7 @@ pub trait Load {
         &mut self,
         entry: &Entry,
         counter: &mut Counter,
+        size: &Size,
     ) -> Result<Option<Weak<RwLock<Node>>>, LoadError>
     where
         Self: NodeExt,
@@ -36,13 +39,29 @@ pub trait Load {
         let is_last_node: bool = *counter == entry.get_name().len();
 
         if is_last_node {
-            *self.get_times_mut() = *entry.get_times();
+            let was_name: bool = self.is_name();
+
+            let current: &mut Entry = self.get_entry_mut();
+            *current.get_times_mut() = *entry.get_times();
+            *current.get_tags_mut() = entry.get_tags().clone();
+            *current.get_metadata_mut() = entry.get_metadata().cloned();
+
+            match (was_name, self.is_name()) {
+                (false, true) => size.names.fetch_add(1, Ordering::Relaxed),
+                (true, false) => size.names.fetch_sub(1, Ordering::Relaxed),
+                _ => 0,
+            };
 
             Ok(None)
         } else {
             let character: &str = &entry.get_name()[*counter..(*counter + 1)];
+            let children: usize = self.get_children().len();
             let next = self.next_child_create(character);
 
+            if self.get_children().len() > children {
+                size.nodes.fetch_add(1, Ordering::Relaxed);
+            }
+
             *counter += 1;
             Ok(Some(next))
         }
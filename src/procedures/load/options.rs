@@ -0,0 +1,53 @@
+use std::{fmt::Display, str::FromStr};
+
+/// What to do when a record in the data file can't be turned into an Entry.
+///
+/// - Skip: Report the record and keep loading the remaining ones.
+/// - Abort: Stop loading and return the error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadRecordPolicy {
+    Skip,
+    Abort,
+}
+
+impl FromStr for BadRecordPolicy {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "abort" => Ok(Self::Abort),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for BadRecordPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::Abort => write!(f, "abort"),
+        }
+    }
+}
+
+/// Where the entries are loaded from, and how invalid data is handled.
+#[derive(Debug, Clone)]
+pub struct LoadOptions {
+    /// Path to the JSON file with the entries.
+    pub path: String,
+    /// What to do with records that have an invalid name or count.
+    pub bad_records: BadRecordPolicy,
+    /// If true, a missing file results in an empty Tree instead of an error.
+    pub allow_missing: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            path: "./names.json".into(),
+            bad_records: BadRecordPolicy::Skip,
+            allow_missing: false,
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::tree::Counter;
+
+/// Number of entries loaded into the Tree, and number of records skipped for being invalid.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LoadSummary {
+    pub source: String,
+    pub loaded: Counter,
+    pub skipped: Counter,
+}
+
+impl LoadSummary {
+    /// Summary for a source that didn't exist, so nothing was loaded.
+    pub fn empty(source: &str) -> Self {
+        LoadSummary {
+            source: source.into(),
+            loaded: 0,
+            skipped: 0,
+        }
+    }
+}
+
+impl Display for LoadSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Loaded {} entries from {}. Skipped {} invalid records.",
+            self.loaded, self.source, self.skipped
+        )
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
-    collections::HashMap,
+    fs::File,
+    io::{BufReader, ErrorKind, Read},
     sync::{Arc, Weak},
 };
 
@@ -7,26 +8,15 @@ use parking_lot::RwLock;
 
 use crate::{
     entry::Entry,
-    log::log_debug,
     node::Node,
-    procedures::load::{node::Load as NodeLoad, LoadError},
+    procedures::load::{
+        json::{stream_json, BATCH_SIZE},
+        node::Load as NodeLoad,
+        LoadError, LoadOptions, LoadSummary,
+    },
     tree::{Counter, TreeExt},
 };
 
-/// Load local 'names.json' file into a HashMap.
-fn load_json() -> HashMap<String, u32> {
-    // Load the file into a string.
-    let input_path = "./names.json";
-    let text = std::fs::read_to_string(input_path).unwrap();
-
-    // Parse the json File
-    let loaded_values: HashMap<String, u32> = serde_json::from_str(&text).unwrap();
-
-    log_debug(&format!("{} elements found.", loaded_values.len()));
-
-    loaded_values
-}
-
 /// Contains all Tree logic for loading entries into the prefix tree.
 pub trait Load {
     /// Include given Entry to prefix tree. Creating new nodes as needed.
@@ -38,13 +28,18 @@ pub trait Load {
     where
         Self: TreeExt,
     {
-        log_debug(&format!("Loading {} .", entry));
+        tracing::trace!(
+            name = entry.get_name(),
+            times = entry.get_times(),
+            "Including entry."
+        );
 
         let mut counter: Counter = 0;
 
         let node: &Arc<RwLock<Node>> = self.get_node();
 
-        let mut traveller: Option<Weak<RwLock<Node>>> = node.write().load(&entry, &mut counter)?;
+        let mut traveller: Option<Weak<RwLock<Node>>> =
+            node.write().load(&entry, &mut counter, self.get_size())?;
 
         loop {
             let next_traveller: Option<Weak<RwLock<Node>>> = match &traveller {
@@ -60,7 +55,7 @@ pub trait Load {
                         Some(value) => {
                             // Run the load method to get the next child
                             let next: Option<Weak<RwLock<Node>>> =
-                                value.write().load(&entry, &mut counter)?;
+                                value.write().load(&entry, &mut counter, self.get_size())?;
 
                             // Return the next child
                             next
@@ -71,23 +66,77 @@ pub trait Load {
             traveller = next_traveller;
         }
 
-        log_debug(&format!("Ran through {} nodes.", counter));
         Ok(())
     }
 
-    /// Load all entries from local 'names.json' file to this Tree.
-    fn load(&self) -> Result<(), LoadError>
+    /// Load all entries from the JSON file in options.path to this Tree.
+    ///
+    /// Returns how many entries were loaded and how many invalid records were skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns Err(LoadError) if the file can't be read or parsed,
+    /// or if a record is invalid and options.bad_records is BadRecordPolicy::Abort.
+    ///
+    /// If the file doesn't exist and options.allow_missing is true, nothing is loaded.
+    fn load(&self, options: &LoadOptions) -> Result<LoadSummary, LoadError>
     where
         Self: TreeExt,
     {
-        let entries: HashMap<String, u32> = load_json();
-
-        for (name, times) in entries {
-            let entry = Entry::new(name, times as u64);
+        let file = match File::open(&options.path) {
+            Ok(value) => value,
+            Err(err) if err.kind() == ErrorKind::NotFound && options.allow_missing => {
+                tracing::warn!(
+                    path = options.path,
+                    "File not found. Starting with an empty Tree."
+                );
+                return Ok(LoadSummary::empty(&options.path));
+            }
+            Err(err) => return Err(LoadError::Io(options.path.clone(), err)),
+        };
+
+        self.load_from(BufReader::new(file), options)
+    }
 
-            self.include(entry)?;
-        }
+    /// Stream a JSON object of names and times from reader into this Tree.
+    ///
+    /// Entries are included in batches as they are deserialized, so the whole file is never held in memory.
+    ///
+    /// # Errors
+    ///
+    /// Same as Load::load, except for a missing file.
+    ///
+    /// ```
+    /// use typerecommendation::{Get, Load, LoadOptions, Tree};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let tree: Tree = Tree::new_empty(10).await;
+    /// let json: &[u8] = br#"{"Ann": 10, "Bob": 5, "": 1}"#;
+    ///
+    /// // The record with an empty name is skipped.
+    /// let summary = tree.load_from(json, &LoadOptions::default()).unwrap();
+    /// assert_eq!((summary.loaded, summary.skipped), (2, 1));
+    ///
+    /// assert_eq!(tree.get_top("b").unwrap()[0].get_name(), "Bob");
+    /// # }
+    /// ```
+    fn load_from<R: Read>(&self, reader: R, options: &LoadOptions) -> Result<LoadSummary, LoadError>
+    where
+        Self: TreeExt,
+    {
+        stream_json(
+            reader,
+            &options.path,
+            BATCH_SIZE,
+            options.bad_records,
+            |batch: &mut Vec<Entry>| {
+                for entry in batch.drain(..) {
+                    self.include(entry)?;
+                }
 
-        Ok(())
+                Ok(())
+            },
+        )
     }
 }
@@ -0,0 +1,6 @@
+pub(crate) mod node;
+mod result;
+pub mod tree;
+
+pub use result::MetadataResult;
+pub(crate) use result::MetadataStep;
@@ -0,0 +1,46 @@
+use crate::{
+    entry::Metadata,
+    node::NodeExt,
+    procedures::metadata::{MetadataResult, MetadataStep},
+    tree::Counter,
+};
+
+/// Contains all Node logic for replacing the metadata of a given name.
+pub trait SetMetadata {
+    /// Checks if current Node is valid for given name.
+    ///
+    /// If not valid, returns next Node to access, does not create new nodes.
+    ///
+    /// If valid, replace the metadata of this Node.
+    ///
+    /// If there are no remaining Nodes to access, or the Node is not a name, returns MetadataResult::NotFound.
+    fn set_metadata(
+        &mut self,
+        name: &str,
+        metadata: &Option<Metadata>,
+        counter: &mut Counter,
+    ) -> MetadataStep
+    where
+        Self: NodeExt,
+    {
+        if *counter == name.len() {
+            if !self.is_name() {
+                return MetadataStep::Done(MetadataResult::NotFound);
+            }
+
+            *self.get_entry_mut().get_metadata_mut() = metadata.clone();
+
+            return MetadataStep::Done(MetadataResult::Success(self.get_entry().clone()));
+        }
+
+        let character: &str = &name[*counter..(*counter + 1)];
+
+        match self.next_child(character) {
+            None => MetadataStep::Done(MetadataResult::NotFound),
+            Some(lock) => {
+                *counter += 1;
+                MetadataStep::Next(lock)
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::Weak;
+
+use parking_lot::RwLock;
+
+use crate::{entry::Entry, node::Node};
+
+/// Result of setting the metadata of a name.
+///
+/// - Success: Entry with the new metadata.
+/// - NotFound: Name was not found on Prefix Tree.
+#[derive(Debug)]
+pub enum MetadataResult {
+    Success(Entry),
+    NotFound,
+}
+
+/// Step of setting the metadata of a name through the Nodes of a Tree.
+///
+/// - Next: Returned by a Node when the search is incomplete. Contains the next Node to access.
+/// - Done: The metadata was set, or the name was not found.
+pub enum MetadataStep {
+    Next(Weak<RwLock<Node>>),
+    Done(MetadataResult),
+}
@@ -0,0 +1,42 @@
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::{
+    entry::Metadata,
+    node::Node,
+    procedures::metadata::{node::SetMetadata as NodeSetMetadata, MetadataResult, MetadataStep},
+    tree::{Counter, TreeExt},
+};
+
+/// Contains all Tree logic for replacing the metadata of a given name.
+pub trait SetMetadata {
+    /// Check Prefix Tree for given name, ignoring capitalization.
+    ///
+    /// If found, replace its metadata and return the Entry. None removes the metadata.
+    /// If Not Found, returns MetadataResult::NotFound.
+    fn set_metadata(&self, name: &str, metadata: Option<Metadata>) -> MetadataResult
+    where
+        Self: TreeExt,
+    {
+        // Nodes are indexed one byte at a time, a non-ASCII name can't be in the Tree.
+        if !name.is_ascii() {
+            return MetadataResult::NotFound;
+        }
+
+        let mut counter: Counter = 0;
+        let mut next: Weak<RwLock<Node>> = Arc::downgrade(self.get_node());
+
+        loop {
+            next = match next
+                .upgrade()
+                .expect("Tried to unlock Node but got None .")
+                .write()
+                .set_metadata(name, &metadata, &mut counter)
+            {
+                MetadataStep::Next(lock) => lock,
+                MetadataStep::Done(result) => return result,
+            };
+        }
+    }
+}
@@ -1,17 +1,22 @@
+pub mod export;
+pub mod find;
 pub mod get;
 pub mod load;
+pub mod metadata;
+pub mod stats;
 pub mod vote;
 
 #[cfg(test)]
 mod tests {
-    use crate::endpoints::rec::prefix::get::Output;
     use crate::entry::Entry;
 
     use crate::tree::Tree;
 
-    use crate::procedures::get::tree::Get;
+    use crate::procedures::get::{tree::Get, TagFilter};
 
-    use super::load::tree::Load;
+    use super::export::{tree::Export, ExportFormat};
+    use super::load::{tree::Load, LoadError, LoadOptions, LoadSummary};
+    use super::stats::{tree::Stats, TreeStats};
     use super::vote::tree::Vote;
 
     fn all_entries() -> Vec<Entry> {
@@ -50,9 +55,7 @@ mod tests {
     fn get_entry(tree: &Tree, name: &str) -> Entry {
         let top: Vec<Entry> = match tree.get_top(name) {
             Err(err) => panic!("{err}"),
-            Ok(value) => match value {
-                Output::Values(values) => values,
-            },
+            Ok(values) => values,
         };
 
         top.first()
@@ -84,9 +87,7 @@ mod tests {
     fn validate_get_all(tree: &Tree, prefix: &str, expected: Vec<Entry>) {
         let top: Vec<Entry> = match tree.get_top(prefix) {
             Err(err) => panic!("{err}"),
-            Ok(value) => match value {
-                Output::Values(values) => values,
-            },
+            Ok(values) => values,
         };
         println!("Entries expected:");
         for i in &expected {
@@ -212,4 +213,145 @@ mod tests {
             validate_vote(&tree, entry.get_name());
         }
     }
+
+    #[tokio::test]
+    async fn get_filtered_by_tags() {
+        let tree: Tree = Tree::new_empty(2).await;
+
+        let tagged = |name: &str, times: u64, tags: &[&str]| -> Entry {
+            Entry::new(name.into(), times)
+                .with_tags(tags.iter().map(|tag| tag.to_string()).collect())
+        };
+
+        for entry in [
+            tagged("ann", 100, &["sales"]),
+            tagged("anna", 90, &["engineering", "inactive"]),
+            tagged("annabel", 80, &["engineering"]),
+            tagged("annie", 70, &["engineering"]),
+            tagged("anton", 60, &[]),
+        ] {
+            tree.include(entry).unwrap();
+        }
+
+        let filter = TagFilter::parse(Some("engineering"), Some("inactive"));
+        let top: Vec<Entry> = tree.get_top_filtered("an", &filter).unwrap();
+
+        // The top 2 is computed among matching entries only
+        let names: Vec<&str> = top.iter().map(|entry| entry.get_name()).collect();
+        assert_eq!(names, ["annabel", "annie"]);
+
+        // The node of the prefix itself is also filtered
+        let filter = TagFilter::parse(None, Some("sales"));
+        let top: Vec<Entry> = tree.get_top_filtered("ann", &filter).unwrap();
+
+        let names: Vec<&str> = top.iter().map(|entry| entry.get_name()).collect();
+        assert_eq!(names, ["anna", "annabel"]);
+    }
+
+    #[tokio::test]
+    async fn load_missing_file() {
+        let tree: Tree = Tree::new_empty(5).await;
+
+        let mut options = LoadOptions {
+            path: "./this-file-does-not-exist.json".into(),
+            ..LoadOptions::default()
+        };
+
+        match tree.load(&options) {
+            Err(LoadError::Io(path, _)) => assert_eq!(path, options.path),
+            Err(err) => panic!("Expected LoadError::Io, got {err} ."),
+            Ok(summary) => panic!("Expected LoadError::Io, got {summary} ."),
+        }
+
+        options.allow_missing = true;
+        let summary: LoadSummary = tree.load(&options).expect("Missing file should be allowed");
+
+        assert_eq!(summary.loaded, 0);
+    }
+
+    #[tokio::test]
+    async fn load_from_reader() {
+        let json: String = format!(
+            "{{ {} }}",
+            all_entries()
+                .iter()
+                .map(|entry| format!("\"{}\": {}", entry.get_name(), entry.get_times()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        let tree: Tree = Tree::new_empty(5).await;
+        let summary: LoadSummary = tree
+            .load_from(json.as_bytes(), &LoadOptions::default())
+            .expect("Failed to load entries from reader");
+
+        assert_eq!(summary.loaded, all_entries().len());
+        assert_eq!(summary.skipped, 0);
+
+        for expected in &all_entries() {
+            validate_get(&tree, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn export() {
+        let tree: Tree = tree().await;
+        tree.vote("Ab");
+
+        let entries: Vec<Entry> = tree.entries().collect();
+        let names: Vec<&str> = entries.iter().map(|entry| entry.get_name()).collect();
+
+        // "cb" is included twice
+        assert_eq!(
+            names,
+            ["a", "aa", "ab", "abc", "acc", "ace", "ba", "bb", "bbc", "cb", "ddd"]
+        );
+        assert_eq!(entries[2], Entry::new("ab".into(), 51));
+        assert_eq!(entries.len(), tree.stats().names);
+
+        let mut out: Vec<u8> = Vec::new();
+        let written = tree.export(ExportFormat::Json, &mut out).unwrap();
+        assert_eq!(written, entries.len());
+
+        // The exported file loads the same entries
+        let copy: Tree = Tree::new_empty(5).await;
+        let summary: LoadSummary = copy
+            .load_from(out.as_slice(), &LoadOptions::default())
+            .unwrap();
+        assert_eq!(summary.loaded, entries.len());
+        assert_eq!(copy.entries().collect::<Vec<Entry>>(), entries);
+
+        assert_eq!(Tree::new_empty(5).await.entries().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn stats() {
+        let tree: Tree = tree().await;
+        let empty: TreeStats = Tree::new_empty(5).await.stats();
+
+        assert_eq!(empty.nodes, 1);
+        assert_eq!(empty.names, 0);
+        assert_eq!(empty.max_depth, 0);
+        assert_eq!(empty.average_branching, 0.0);
+
+        let stats: TreeStats = tree.stats();
+
+        // "cb" is included twice
+        assert_eq!(stats.nodes, 17);
+        assert_eq!(stats.names, 11);
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.average_branching, 16.0 / 9.0);
+        assert!(stats.approx_heap_bytes > 17 * std::mem::size_of::<crate::node::Node>());
+
+        // Counted while including, without a traversal
+        assert_eq!(tree.nodes(), stats.nodes);
+        assert_eq!(tree.names(), stats.names);
+        assert_eq!(Tree::new_empty(5).await.nodes(), 1);
+
+        // Including a name again, or with times 0, updates the count of names
+        tree.include(Entry::new("ab".into(), 0)).unwrap();
+        tree.include(Entry::new("bb".into(), 1)).unwrap();
+        assert_eq!(tree.names(), stats.names - 1);
+        assert_eq!(tree.nodes(), stats.nodes);
+    }
 }
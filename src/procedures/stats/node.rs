@@ -0,0 +1,54 @@
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::{Arc, Weak},
+};
+
+use parking_lot::RwLock;
+
+use crate::node::NodeExt;
+
+/// Contains all Node logic for measuring the Tree.
+pub trait Stats {
+    /// Approximate number of bytes this Node uses on the heap, including its own allocation.
+    ///
+    /// Counts the allocated capacity of strings and of the children map,
+    /// estimates the size of tags and metadata from their contents.
+    fn heap_size(&self) -> usize
+    where
+        Self: NodeExt + Sized,
+    {
+        // The Node itself lives in the allocation of its Arc, next to the strong and weak counts.
+        let mut size: usize = size_of::<RwLock<Self>>() + 2 * size_of::<usize>();
+
+        let entry = self.get_entry();
+        size += entry.get_name().len();
+
+        size += entry
+            .get_tags()
+            .iter()
+            .map(|tag| size_of::<String>() + tag.capacity())
+            .sum::<usize>();
+
+        // Serialized length is close enough to the size of the parsed values.
+        size += entry.get_metadata().map_or(0, |metadata| {
+            serde_json::to_string(metadata).map_or(0, |s| s.len())
+        });
+
+        let children: &HashMap<String, Arc<RwLock<Self>>> = self.get_children();
+
+        // Each bucket holds a key, a value and a control byte.
+        size += children.capacity() * (size_of::<(String, Arc<RwLock<Self>>)>() + 1);
+        size += children.keys().map(String::capacity).sum::<usize>();
+
+        size
+    }
+
+    /// Weak pointers to every child of this Node, so they can be visited after this lock is released.
+    fn child_locks(&self) -> Vec<Weak<RwLock<Self>>>
+    where
+        Self: NodeExt + Sized,
+    {
+        self.get_children().values().map(Arc::downgrade).collect()
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Structure of a Tree at the moment it was traversed.
+///
+/// - nodes: Number of Nodes, including the starting Node.
+/// - names: Number of Nodes that are names.
+/// - max_depth: Length of the longest prefix. The starting Node has depth 0.
+/// - average_branching: Average number of children of the Nodes that have children.
+/// - approx_heap_bytes: Estimate of the memory used by the Nodes and their entries.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct TreeStats {
+    pub nodes: usize,
+    pub names: usize,
+    pub max_depth: usize,
+    pub average_branching: f64,
+    pub approx_heap_bytes: usize,
+}
+
+impl fmt::Display for TreeStats {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{} nodes, {} names, max depth {}, average branching {:.2}, about {} bytes.",
+            self.nodes, self.names, self.max_depth, self.average_branching, self.approx_heap_bytes
+        )
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::{Arc, Weak};
+
+use parking_lot::RwLock;
+
+use crate::{
+    node::{Node, NodeExt},
+    procedures::stats::{node::Stats as NodeStats, TreeStats},
+    tree::TreeExt,
+};
+
+/// Contains all Tree logic for measuring the Tree.
+pub trait Stats {
+    /// Traverse every Node and measure the Tree.
+    ///
+    /// Only one Node is locked at a time, so requests keep running during the traversal.
+    /// Entries included or removed meanwhile may or may not be counted.
+    fn stats(&self) -> TreeStats
+    where
+        Self: TreeExt,
+    {
+        let mut stats = TreeStats::default();
+        let mut parents: usize = 0;
+        let mut children: usize = 0;
+
+        let mut pending: Vec<(Weak<RwLock<Node>>, usize)> =
+            Vec::from([(Arc::downgrade(self.get_node()), 0)]);
+
+        while let Some((lock, depth)) = pending.pop() {
+            // A Node dropped during the traversal is simply not counted.
+            let Some(node) = lock.upgrade() else {
+                continue;
+            };
+
+            let node = node.read();
+
+            stats.nodes += 1;
+            stats.max_depth = stats.max_depth.max(depth);
+            stats.approx_heap_bytes += node.heap_size();
+
+            if node.is_name() {
+                stats.names += 1;
+            }
+
+            let locks: Vec<Weak<RwLock<Node>>> = node.child_locks();
+
+            if !locks.is_empty() {
+                parents += 1;
+                children += locks.len();
+            }
+
+            pending.extend(locks.into_iter().map(|lock| (lock, depth + 1)));
+        }
+
+        if parents > 0 {
+            stats.average_branching = children as f64 / parents as f64;
+        }
+
+        stats
+    }
+}
@@ -1,5 +1,6 @@
-pub mod node;
+pub(crate) mod node;
 mod result;
 pub mod tree;
 
 pub use result::VoteResult;
+pub(crate) use result::VoteStep;
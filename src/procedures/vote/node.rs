@@ -1,5 +1,8 @@
 use crate::{
-    entry::Entry, log::log_debug, node::NodeExt, procedures::vote::VoteResult, tree::Counter,
+    entry::Entry,
+    node::NodeExt,
+    procedures::vote::{VoteResult, VoteStep},
+    tree::Counter,
 };
 
 /// Contains all Node logic for incrementing 'times' related to a given name.
@@ -8,45 +11,41 @@ pub trait Vote {
     ///
     /// If not valid, returns next Node to access, does not create new nodes.
     ///
-    /// If valid, increment 'entry.times' on this Node.
+    /// If valid, increment 'entry.times' on this Node by amount.
     ///
     /// If there are no remaining Nodes to access, returns VoteResult::NotFound.
-    fn vote(&mut self, name: &str, counter: &mut Counter) -> VoteResult
+    fn vote(&mut self, name: &str, amount: u64, counter: &mut Counter) -> VoteStep
     where
         Self: NodeExt,
     {
-        log_debug("\nFunction crate::procedures::vote::Vote::vote\n");
+        tracing::trace!(
+            node = self.get_prefix(),
+            times = self.get_times(),
+            "Visiting node."
+        );
         let is_last_node: bool = *counter == name.len();
 
         if is_last_node {
             if !self.is_name() {
                 // The node exists but it is not a name
-                return VoteResult::NotFound;
+                return VoteStep::Done(VoteResult::NotFound);
             }
 
-            let times = self.get_times() + 1;
-            let entry: Entry = Entry::new(self.get_prefix().into(), times);
+            let times = self.get_times().saturating_add(amount);
             *self.get_times_mut() = times;
 
-            return VoteResult::Success(entry);
+            let entry: Entry = self.get_entry().clone();
+
+            return VoteStep::Done(VoteResult::Success(entry));
         }
 
         let character: &str = &name[*counter..(*counter + 1)];
-        log_debug(&format!(
-            "Node for character {character} counter {}",
-            *counter
-        ));
 
         match self.next_child(character) {
-            None => {
-                log_debug("Not found");
-                VoteResult::NotFound
-            }
+            None => VoteStep::Done(VoteResult::NotFound),
             Some(lock) => {
                 *counter += 1;
-                log_debug("Found");
-
-                VoteResult::Next(lock)
+                VoteStep::Next(lock)
             }
         }
     }
@@ -3,47 +3,96 @@ use std::sync::{Arc, Weak};
 use parking_lot::RwLock;
 
 use crate::{
-    log::log_debug,
     node::Node,
-    procedures::vote::{node::Vote as NodeVote, VoteResult},
+    procedures::vote::{node::Vote as NodeVote, VoteResult, VoteStep},
+    tree::Votes,
 };
 
-pub use crate::tree::TreeExt;
+pub(crate) use crate::tree::TreeExt;
 
 /// Contains all Tree logic for incrementing 'times' related to a given name.
 pub trait Vote {
     /// Check Prefix Tree for given name.
     ///
-    /// If found, increment 'times' and return Entry.
+    /// If found, increment 'times', send Entry to the subscribers of the Tree and return it.
     /// If Not Found, returns VoteResult::NotFound.
+    ///
+    /// ```
+    /// use typerecommendation::{Load, Tree, Vote, VoteResult};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let tree: Tree = Tree::new_empty(10).await;
+    /// tree.include(("Ann", 10).into()).unwrap();
+    ///
+    /// let mut votes = tree.subscribe();
+    ///
+    /// match tree.vote("ANN") {
+    ///     VoteResult::Success(entry) => assert_eq!(*entry.get_times(), 11),
+    ///     VoteResult::NotFound => unreachable!(),
+    /// }
+    /// assert_eq!(votes.try_recv().unwrap().get_name(), "Ann");
+    ///
+    /// assert!(matches!(tree.vote("Bob"), VoteResult::NotFound));
+    /// # }
+    /// ```
     fn vote(&self, name: &str) -> VoteResult
     where
         Self: TreeExt,
     {
-        log_debug("------------------------");
+        let result: VoteResult = self.vote_by(name, 1);
+
+        if let VoteResult::Success(entry) = &result {
+            let feed = self.get_feed();
+
+            // Sending fails when nobody is subscribed, which is fine.
+            if feed.receiver_count() > 0 {
+                let _ = feed.send(entry.clone());
+            }
+        }
+
+        result
+    }
+
+    /// Same as Vote::vote, but increments 'times' by amount, without sending Entry to the subscribers.
+    ///
+    /// Used to carry votes over to a reloaded Tree, which are not new votes.
+    fn vote_by(&self, name: &str, amount: u64) -> VoteResult
+    where
+        Self: TreeExt,
+    {
         let mut counter: usize = 0;
 
         let mut next: Weak<RwLock<Node>> = Arc::downgrade(self.get_node());
 
-        log_debug("Starting loop post_entry");
         loop {
-            log_debug("------------------------");
             next = match next
                 .upgrade()
                 .expect("Tried to unlock Node but got None .")
                 .write()
-                .vote(name, &mut counter)
+                .vote(name, amount, &mut counter)
             {
-                VoteResult::Next(lock) => {
-                    log_debug("post_entry Next");
-                    lock
-                }
-                VoteResult::NotFound => {
-                    log_debug("post_entry Not Found");
+                VoteStep::Next(lock) => lock,
+                VoteStep::Done(VoteResult::NotFound) => {
+                    tracing::debug!(name, "Name not found.");
                     return VoteResult::NotFound;
                 }
-                success => return success,
+                VoteStep::Done(VoteResult::Success(entry)) => {
+                    let mut votes = self.get_votes().lock();
+                    let times: &mut u64 = votes.entry(entry.get_name().into()).or_default();
+                    *times = times.saturating_add(amount);
+
+                    return VoteResult::Success(entry);
+                }
             };
         }
     }
+
+    /// Remove and return all votes applied since the Tree was loaded.
+    fn take_votes(&self) -> Votes
+    where
+        Self: TreeExt,
+    {
+        std::mem::take(&mut *self.get_votes().lock())
+    }
 }
@@ -0,0 +1,20 @@
+use crate::{env::SuggestionNumber, procedures::load::LoadOptions};
+
+/// Name of the dictionary used when no dictionaries are configured.
+pub const DEFAULT_DICTIONARY: &str = "default";
+
+/// Settings for a single named dictionary.
+#[derive(Debug, Clone)]
+pub struct DictionaryConfig {
+    pub name: String,
+    pub suggestion_number: SuggestionNumber,
+    pub load_options: LoadOptions,
+}
+
+/// Dictionary names are used as a path segment, so only lowercase letters, digits, '-' and '_' are allowed.
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
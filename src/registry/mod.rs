@@ -0,0 +1,108 @@
+mod config;
+
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{dedupe::Dedupe, personal::Overlay, reload::Reloader, tree::Tree};
+
+pub use config::{is_valid_name, DictionaryConfig, DEFAULT_DICTIONARY};
+
+/// A Tree that can be reloaded, the personal votes of its users, and its recent votes.
+///
+/// Can be cloned. All clones share the same Tree, users and recent votes.
+#[derive(Clone)]
+pub struct Dictionary {
+    pub reloader: Reloader,
+    pub overlay: Overlay,
+    pub dedupe: Dedupe,
+}
+
+impl Dictionary {
+    /// Returns the Tree that new requests should use.
+    pub fn current(&self) -> Tree {
+        self.reloader.current()
+    }
+}
+
+#[cfg(test)]
+impl Dictionary {
+    /// A Dictionary serving tree, with the default personal and dedupe options.
+    pub fn for_tests(tree: Tree) -> Self {
+        use crate::{dedupe::DedupeOptions, personal::PersonalOptions};
+
+        Dictionary {
+            reloader: Reloader::for_tests(tree),
+            overlay: Overlay::new(PersonalOptions::default()),
+            dedupe: Dedupe::new(DedupeOptions::default()),
+        }
+    }
+
+    /// A Dictionary whose Tree is still loading.
+    pub async fn loading_for_tests() -> Self {
+        use crate::procedures::load::LoadOptions;
+
+        let reloader = Reloader::pending(5, LoadOptions::default(), true).await;
+
+        Dictionary {
+            reloader,
+            ..Dictionary::for_tests(Tree::new_empty(5).await)
+        }
+    }
+}
+
+/// Named dictionaries served by this process, each with its own Tree and data file.
+///
+/// One of them is the default dictionary, used by the routes that don't name a dictionary.
+///
+/// Can be cloned. All clones share the same dictionaries.
+#[derive(Clone)]
+pub struct Registry {
+    dictionaries: Arc<HashMap<String, Dictionary>>,
+    default: String,
+}
+
+impl Registry {
+    /// Creates a Registry with given dictionaries.
+    ///
+    /// # Panics
+    ///
+    /// If default is not one of the dictionaries.
+    pub fn new(dictionaries: HashMap<String, Dictionary>, default: String) -> Self {
+        assert!(
+            dictionaries.contains_key(&default),
+            "Default dictionary {default} is not registered."
+        );
+
+        Registry {
+            dictionaries: Arc::new(dictionaries),
+            default,
+        }
+    }
+
+    /// Returns the dictionary with given name.
+    pub fn get(&self, name: &str) -> Option<&Dictionary> {
+        self.dictionaries.get(name)
+    }
+
+    /// Returns the dictionary used by the routes that don't name a dictionary.
+    pub fn get_default(&self) -> &Dictionary {
+        self.dictionaries
+            .get(&self.default)
+            .expect("Default dictionary is always registered.")
+    }
+
+    /// Iterate through all dictionaries and their names.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Dictionary)> {
+        self.dictionaries.iter()
+    }
+}
+
+#[cfg(test)]
+impl Registry {
+    /// A Registry with dictionary as its default, and only, dictionary.
+    pub fn for_tests(dictionary: Dictionary) -> Self {
+        Registry::new(
+            HashMap::from([(DEFAULT_DICTIONARY.to_string(), dictionary)]),
+            DEFAULT_DICTIONARY.into(),
+        )
+    }
+}
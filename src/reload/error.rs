@@ -0,0 +1,25 @@
+use std::fmt::Display;
+
+use crate::procedures::load::LoadError;
+
+#[derive(Debug)]
+pub enum ReloadError {
+    // Another reload hasn't finished yet
+    InProgress,
+    Load(LoadError),
+}
+
+impl Display for ReloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InProgress => write!(f, "A reload is already in progress"),
+            Self::Load(err) => write!(f, "Reload failed, keeping the current Tree. {}", err),
+        }
+    }
+}
+
+impl From<LoadError> for ReloadError {
+    fn from(err: LoadError) -> Self {
+        Self::Load(err)
+    }
+}
@@ -0,0 +1,202 @@
+mod error;
+mod signal;
+mod summary;
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use parking_lot::RwLock;
+
+use crate::{
+    env::SuggestionNumber,
+    metrics::{ENTRIES, NODES},
+    procedures::{
+        load::{LoadOptions, LoadSummary},
+        vote::{tree::Vote, VoteResult},
+    },
+    tree::Tree,
+};
+
+pub use error::ReloadError;
+pub use signal::listen_sighup;
+pub use summary::ReloadSummary;
+
+/// Holds the Tree being served, and rebuilds it from the configured data file on request.
+///
+/// Can be cloned. All clones share the same Tree.
+#[derive(Clone)]
+pub struct Reloader {
+    tree: Arc<RwLock<Tree>>,
+    suggestions: SuggestionNumber,
+    options: LoadOptions,
+
+    // If true, votes applied to the old Tree are applied again to the new one
+    keep_votes: bool,
+    reloading: Arc<AtomicBool>,
+
+    // False until the data file has been loaded once
+    ready: Arc<AtomicBool>,
+
+    // Dictionary whose entry and node gauges are set after each reload
+    gauges: Option<String>,
+}
+
+// Resets the reloading flag even if the reload future is dropped before finishing.
+struct ReloadGuard<'a>(&'a AtomicBool);
+
+impl<'a> Drop for ReloadGuard<'a> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl Reloader {
+    /// Serve tree, rebuilding it with suggestions and options when reloaded.
+    pub fn new(
+        tree: Tree,
+        suggestions: SuggestionNumber,
+        options: LoadOptions,
+        keep_votes: bool,
+    ) -> Self {
+        Reloader {
+            tree: Arc::new(RwLock::new(tree)),
+            suggestions,
+            options,
+            keep_votes,
+            reloading: Arc::new(AtomicBool::new(false)),
+            ready: Arc::new(AtomicBool::new(true)),
+            gauges: None,
+        }
+    }
+
+    /// Set the entry and node gauges of dictionary to the size of the current Tree, and again after each reload.
+    pub fn with_gauges(mut self, dictionary: &str) -> Self {
+        self.gauges = Some(dictionary.into());
+        self.set_gauges(&self.current());
+        self
+    }
+
+    // Entries are only included while loading, so the size of a loaded Tree is known.
+    fn set_gauges(&self, tree: &Tree) {
+        if let Some(dictionary) = &self.gauges {
+            ENTRIES
+                .with_label_values(&[dictionary])
+                .set(tree.names() as i64);
+            NODES
+                .with_label_values(&[dictionary])
+                .set(tree.nodes() as i64);
+        }
+    }
+
+    /// Serve an empty Tree until the first reload loads the data file.
+    pub async fn pending(
+        suggestions: SuggestionNumber,
+        options: LoadOptions,
+        keep_votes: bool,
+    ) -> Self {
+        let reloader = Reloader::new(
+            Tree::new_empty(suggestions).await,
+            suggestions,
+            options,
+            keep_votes,
+        );
+        reloader.ready.store(false, Ordering::SeqCst);
+
+        reloader
+    }
+
+    /// If the data file has been loaded at least once.
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+
+    /// If a reload is running.
+    pub fn is_reloading(&self) -> bool {
+        self.reloading.load(Ordering::SeqCst)
+    }
+
+    /// Returns the Tree that new requests should use.
+    ///
+    /// A request keeps the Tree it received until it finishes, even if a reload happens meanwhile.
+    pub fn current(&self) -> Tree {
+        self.tree.read().clone()
+    }
+
+    /// Path of the data file the Tree is loaded from.
+    pub fn source(&self) -> &str {
+        &self.options.path
+    }
+
+    /// Build a new Tree from the data file in the background and swap it with the current one.
+    ///
+    /// # Errors
+    ///
+    /// If another reload is running, returns ReloadError::InProgress.
+    /// If loading fails, the current Tree is kept and ReloadError::Load is returned.
+    pub async fn reload(&self) -> Result<ReloadSummary, ReloadError> {
+        if self
+            .reloading
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(ReloadError::InProgress);
+        }
+
+        let _guard = ReloadGuard(&self.reloading);
+
+        let (tree, load): (Tree, LoadSummary) =
+            Tree::new(self.suggestions, self.options.clone()).await?;
+
+        let current: Tree = self.current();
+
+        // Subscribers keep receiving votes after the reload.
+        let tree: Tree = tree.with_feed_of(&current);
+
+        let mut summary = ReloadSummary {
+            load,
+            carried_votes: 0,
+            dropped_votes: 0,
+        };
+
+        // Votes are carried over before the swap, so readers never see the new Tree without them.
+        if self.keep_votes {
+            carry_votes(&current, &tree, &mut summary);
+        }
+
+        let old: Tree = std::mem::replace(&mut *self.tree.write(), tree.clone());
+
+        // Votes that reached the old Tree while the first ones were carried over.
+        // Only requests that still hold the old Tree can vote on it after this.
+        if self.keep_votes {
+            carry_votes(&old, &tree, &mut summary);
+        }
+
+        self.ready.store(true, Ordering::SeqCst);
+
+        self.set_gauges(&tree);
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+impl Reloader {
+    /// A Reloader serving tree, with the default load options, that keeps votes on reload.
+    pub fn for_tests(tree: Tree) -> Self {
+        let suggestions: SuggestionNumber = tree.get_suggestions();
+
+        Reloader::new(tree, suggestions, LoadOptions::default(), true)
+    }
+}
+
+/// Apply the votes of from since it was loaded to to, counting them in summary.
+fn carry_votes(from: &Tree, to: &Tree, summary: &mut ReloadSummary) {
+    for (name, times) in from.take_votes() {
+        match to.vote_by(&name, times) {
+            VoteResult::Success(_) => summary.carried_votes += times,
+            _ => summary.dropped_votes += times,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::reload::Reloader;
+
+/// Reload every given Tree each time the process receives SIGHUP.
+#[cfg(unix)]
+pub fn listen_sighup(reloaders: Vec<Reloader>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("Failed to listen to SIGHUP.");
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            for reloader in &reloaders {
+                tracing::info!(source = reloader.source(), "SIGHUP received. Reloading.");
+
+                match reloader.reload().await {
+                    Ok(summary) => tracing::info!("{summary}"),
+                    Err(err) => tracing::error!("{err}"),
+                }
+            }
+        }
+    });
+}
+
+/// SIGHUP only exists on unix. Reloading is still available through the admin endpoint.
+#[cfg(not(unix))]
+pub fn listen_sighup(_reloaders: Vec<Reloader>) {
+    // Doesn't do anything outside of unix.
+}
@@ -0,0 +1,26 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::procedures::load::LoadSummary;
+
+/// Result of loading the new Tree, and how many of the previous votes were applied to it.
+///
+/// Votes for names that no longer exist in the data file are dropped.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadSummary {
+    #[serde(flatten)]
+    pub load: LoadSummary,
+    pub carried_votes: u64,
+    pub dropped_votes: u64,
+}
+
+impl Display for ReloadSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} Carried over {} votes, dropped {}.",
+            self.load, self.carried_votes, self.dropped_votes
+        )
+    }
+}
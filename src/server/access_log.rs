@@ -0,0 +1,454 @@
+use std::{
+    cell::Cell,
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, LineWriter, Write},
+    net::SocketAddr,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use serde::Serialize;
+use time::{format_description::well_known::Rfc3339, macros::format_description, OffsetDateTime};
+use warp::{
+    http::{header, HeaderMap, Method, StatusCode, Version},
+    log::{Info, Log},
+};
+
+/// Layout of the access log lines.
+///
+/// - Combined: Apache combined log format, followed by the request ID and latency in microseconds.
+/// - Json: One JSON object per request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogFormat {
+    Combined,
+    Json,
+}
+
+impl FromStr for AccessLogFormat {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "combined" => Ok(Self::Combined),
+            "json" => Ok(Self::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for AccessLogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Combined => write!(f, "combined"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Where the access log is written, if anywhere.
+#[derive(Debug, Clone)]
+pub struct AccessLogOptions {
+    /// File the lines are appended to. If None, there is no access log.
+    pub path: Option<String>,
+    pub format: AccessLogFormat,
+}
+
+impl Default for AccessLogOptions {
+    fn default() -> Self {
+        AccessLogOptions {
+            path: None,
+            format: AccessLogFormat::Combined,
+        }
+    }
+}
+
+/// Everything written about a request in the access log.
+pub struct Access<'a> {
+    pub id: &'a str,
+    pub remote: Option<SocketAddr>,
+    pub method: &'a Method,
+    pub uri: &'a str,
+    pub version: Version,
+    pub headers: &'a HeaderMap,
+    pub status: StatusCode,
+    pub bytes: Option<u64>,
+    pub time: SystemTime,
+    pub latency: Duration,
+}
+
+/// Lines waiting to be written. Lines for requests beyond these are dropped.
+const PENDING_LINES: usize = 8192;
+
+/// Appends one line per request to the access log file.
+///
+/// Lines are written by a dedicated thread, so requests never wait for the file.
+/// If the thread falls behind, new lines are dropped and counted instead.
+///
+/// Can be cloned. All clones write to the same file.
+#[derive(Clone)]
+pub struct AccessLog {
+    lines: SyncSender<String>,
+    format: AccessLogFormat,
+
+    // Lines dropped since the writer last reported them
+    dropped: Arc<AtomicU64>,
+}
+
+impl AccessLog {
+    /// Open the access log file in options, creating it if needed.
+    ///
+    /// Returns None if there is no access log path.
+    pub fn open(options: &AccessLogOptions) -> io::Result<Option<Self>> {
+        let path: &str = match &options.path {
+            None => return Ok(None),
+            Some(value) => value,
+        };
+
+        let file: File = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let (lines, pending) = mpsc::sync_channel::<String>(PENDING_LINES);
+        let dropped: Arc<AtomicU64> = Arc::new(AtomicU64::new(0));
+
+        let writer_dropped: Arc<AtomicU64> = Arc::clone(&dropped);
+        std::thread::Builder::new()
+            .name("access-log".into())
+            .spawn(move || write_lines(pending, LineWriter::new(file), writer_dropped))?;
+
+        Ok(Some(AccessLog {
+            lines,
+            format: options.format,
+            dropped,
+        }))
+    }
+
+    /// Queue a line for access. The request is not affected by failures or a full queue.
+    pub fn write(&self, access: &Access) {
+        let line: String = match self.format {
+            AccessLogFormat::Combined => combined(access),
+            AccessLogFormat::Json => json(access),
+        };
+
+        match self.lines.try_send(line) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                tracing::error!("Failed to write access log. The writer thread stopped.");
+            }
+        }
+    }
+}
+
+/// What the access log needs to know about a response, that warp::log::Info doesn't have.
+struct Answer {
+    id: String,
+    remote: Option<SocketAddr>,
+    bytes: Option<u64>,
+}
+
+thread_local! {
+    // Set by answered when a response is made, and taken by the access filter right after,
+    // in the same poll of the request future, so it never holds the answer to another request.
+    static ANSWER: Cell<Option<Answer>> = const { Cell::new(None) };
+}
+
+/// Pass the request ID, client address and body length of the response just made on to the access filter.
+///
+/// Called by request_id::answer. A warp::log::custom filter only sees the request,
+/// and not the values extracted by the filters it wraps, like the ClientAddr of TLS connections.
+pub fn answered(id: String, remote: Option<SocketAddr>, bytes: Option<u64>) {
+    ANSWER.set(Some(Answer { id, remote, bytes }));
+}
+
+/// Write a line to access_log for each response, if there is an access log.
+///
+/// Wraps routes whose responses go through request_id::answer.
+/// Streamed responses are written when their headers are sent,
+/// so their bytes are unknown and their latency is the time to the first byte.
+pub fn access(access_log: Option<AccessLog>) -> Log<impl Fn(Info) + Clone> {
+    warp::log::custom(move |info: Info| {
+        let answer: Option<Answer> = ANSWER.take();
+
+        let Some(access_log) = &access_log else {
+            return;
+        };
+
+        let (id, remote, bytes): (&str, Option<SocketAddr>, Option<u64>) = match &answer {
+            Some(answer) => (&answer.id, answer.remote, answer.bytes),
+            None => ("-", info.remote_addr(), None),
+        };
+
+        access_log.write(&Access {
+            id,
+            remote,
+            method: info.method(),
+            uri: info.path(),
+            version: info.version(),
+            headers: info.request_headers(),
+            status: info.status(),
+            bytes,
+            time: SystemTime::now() - info.elapsed(),
+            latency: info.elapsed(),
+        });
+    })
+}
+
+/// Write each line received to out, until every AccessLog is dropped.
+fn write_lines(pending: Receiver<String>, mut out: impl Write, dropped: Arc<AtomicU64>) {
+    for line in pending {
+        if let Err(err) = writeln!(out, "{line}") {
+            tracing::error!("Failed to write access log. {err}");
+        }
+
+        let count: u64 = dropped.swap(0, Ordering::Relaxed);
+        if count > 0 {
+            tracing::warn!("Dropped {count} access log lines, the file couldn't keep up.");
+        }
+    }
+
+    let _ = out.flush();
+}
+
+fn header_value(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+/// `host - - [day/month/year:hour:minute:second +0000] "request" status bytes "referer" "user agent" id latency`
+fn combined(access: &Access) -> String {
+    let remote: String = access
+        .remote
+        .map_or_else(|| "-".into(), |addr| addr.ip().to_string());
+    let bytes: String = access
+        .bytes
+        .map_or_else(|| "-".into(), |bytes| bytes.to_string());
+
+    // Quotes in headers would end the field early.
+    let quoted = |name: header::HeaderName| -> String {
+        header_value(access.headers, name)
+            .map_or_else(|| "-".into(), |value| value.replace('"', "\\\""))
+    };
+
+    format!(
+        "{remote} - - [{}] \"{} {} {:?}\" {} {bytes} \"{}\" \"{}\" {} {}",
+        apache_time(access.time),
+        access.method,
+        access.uri,
+        access.version,
+        access.status.as_u16(),
+        quoted(header::REFERER),
+        quoted(header::USER_AGENT),
+        access.id,
+        access.latency.as_micros(),
+    )
+}
+
+/// Body of the JSON access log lines.
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    time: String,
+    request_id: &'a str,
+    remote: Option<String>,
+    method: &'a str,
+    uri: &'a str,
+    version: String,
+    status: u16,
+    bytes: Option<u64>,
+    referer: Option<&'a str>,
+    user_agent: Option<&'a str>,
+    latency_us: u128,
+}
+
+fn json(access: &Access) -> String {
+    let line = JsonLine {
+        time: rfc3339_time(access.time),
+        request_id: access.id,
+        remote: access.remote.map(|addr| addr.ip().to_string()),
+        method: access.method.as_str(),
+        uri: access.uri,
+        version: format!("{:?}", access.version),
+        status: access.status.as_u16(),
+        bytes: access.bytes,
+        referer: header_value(access.headers, header::REFERER),
+        user_agent: header_value(access.headers, header::USER_AGENT),
+        latency_us: access.latency.as_micros(),
+    };
+
+    serde_json::to_string(&line).expect("Access log line is always serializable.")
+}
+
+/// `10/Oct/2000:13:55:36 +0000`
+fn apache_time(time: SystemTime) -> String {
+    OffsetDateTime::from(time)
+        .format(format_description!(
+            "[day]/[month repr:short]/[year]:[hour]:[minute]:[second] +0000"
+        ))
+        .expect("UTC times can be formatted.")
+}
+
+/// `2000-10-10T13:55:36Z`
+fn rfc3339_time(time: SystemTime) -> String {
+    OffsetDateTime::from(time)
+        .replace_nanosecond(0)
+        .expect("0 is a valid nanosecond.")
+        .format(&Rfc3339)
+        .expect("UTC times can be formatted.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::UNIX_EPOCH;
+
+    use warp::Filter;
+
+    use crate::server::{
+        client::remote,
+        request_id::{answer, request_id},
+    };
+
+    fn sample_access<'a>(headers: &'a HeaderMap, method: &'a Method) -> Access<'a> {
+        Access {
+            id: "abc",
+            remote: Some("127.0.0.1:5000".parse().unwrap()),
+            method,
+            uri: "/rec/am",
+            version: Version::HTTP_11,
+            headers,
+            status: StatusCode::OK,
+            bytes: Some(42),
+            time: UNIX_EPOCH + Duration::from_secs(971_186_136),
+            latency: Duration::from_micros(250),
+        }
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(apache_time(UNIX_EPOCH), "01/Jan/1970:00:00:00 +0000");
+
+        let time: SystemTime = UNIX_EPOCH + Duration::from_secs(971_186_136);
+        assert_eq!(apache_time(time), "10/Oct/2000:13:55:36 +0000");
+        assert_eq!(rfc3339_time(time), "2000-10-10T13:55:36Z");
+
+        let leap_day: SystemTime = UNIX_EPOCH + Duration::from_millis(1_709_164_800_250);
+        assert_eq!(rfc3339_time(leap_day), "2024-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn written_in_the_background() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("access.log");
+
+        let options = AccessLogOptions {
+            path: Some(path.to_string_lossy().into()),
+            format: AccessLogFormat::Combined,
+        };
+        let log: AccessLog = AccessLog::open(&options).unwrap().unwrap();
+
+        let headers = HeaderMap::new();
+        let method = Method::GET;
+        log.write(&sample_access(&headers, &method));
+        log.write(&sample_access(&headers, &method));
+
+        let mut contents = String::new();
+        for _ in 0..100 {
+            contents = std::fs::read_to_string(&path).unwrap();
+            if contents.lines().count() == 2 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(contents.lines().count(), 2);
+        assert!(contents.starts_with("127.0.0.1 - - [10/Oct/2000:13:55:36 +0000]"));
+    }
+
+    #[tokio::test]
+    async fn requests_are_written_with_their_id() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("access.log");
+
+        let options = AccessLogOptions {
+            path: Some(path.to_string_lossy().into()),
+            format: AccessLogFormat::Json,
+        };
+        let log: Option<AccessLog> = AccessLog::open(&options).unwrap();
+
+        let filter = request_id()
+            .and(remote())
+            .and(warp::path!("rec" / String))
+            .map(answer)
+            .with(access(log));
+
+        let response = warp::test::request()
+            .path("/rec/am")
+            .remote_addr("203.0.113.7:5000".parse().unwrap())
+            .header(header::USER_AGENT, "curl/8.0")
+            .reply(&filter)
+            .await;
+        let id = response.headers()["x-request-id"].to_str().unwrap();
+
+        let mut contents = String::new();
+        for _ in 0..100 {
+            contents = std::fs::read_to_string(&path).unwrap();
+            if !contents.is_empty() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let line: serde_json::Value = serde_json::from_str(contents.trim_end()).unwrap();
+        assert_eq!(line["request_id"], id);
+        assert_eq!(line["remote"], "203.0.113.7");
+        assert_eq!(line["uri"], "/rec/am");
+        assert_eq!(line["status"], 200);
+        assert_eq!(line["bytes"], 2);
+        assert_eq!(line["user_agent"], "curl/8.0");
+    }
+
+    #[test]
+    fn full_queue_drops_lines() {
+        // Nobody receives, so the queue stays full.
+        let (lines, _pending) = mpsc::sync_channel::<String>(1);
+        let log = AccessLog {
+            lines,
+            format: AccessLogFormat::Json,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+
+        let headers = HeaderMap::new();
+        let method = Method::GET;
+        for _ in 0..3 {
+            log.write(&sample_access(&headers, &method));
+        }
+
+        assert_eq!(log.dropped.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn lines() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::USER_AGENT, "curl/8.0".parse().unwrap());
+        let method = Method::GET;
+
+        assert_eq!(
+            combined(&sample_access(&headers, &method)),
+            "127.0.0.1 - - [10/Oct/2000:13:55:36 +0000] \"GET /rec/am HTTP/1.1\" 200 42 \"-\" \"curl/8.0\" abc 250"
+        );
+
+        let line: serde_json::Value =
+            serde_json::from_str(&json(&sample_access(&headers, &method))).unwrap();
+        assert_eq!(line["request_id"], "abc");
+        assert_eq!(line["remote"], "127.0.0.1");
+        assert_eq!(line["status"], 200);
+        assert_eq!(line["referer"], serde_json::Value::Null);
+        assert_eq!(line["user_agent"], "curl/8.0");
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    convert::Infallible,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use tracing::Span;
+use warp::{http::HeaderMap, Filter};
+
+/// Request extension with the address of the connection the request arrived on.
+///
+/// Set by serve_tls, because warp only knows the address of the connections it accepts itself.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientAddr(pub SocketAddr);
+
+/// Header set by proxies with the addresses a request was forwarded for.
+const FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Address of the client that sent a request.
+///
+/// The X-Forwarded-For header is only used if peer is one of trusted, the proxies in front of the server.
+/// Its addresses are read from the right, skipping trusted proxies, so a client can't pick its own address
+/// by sending the header. If the header is missing or invalid, peer is used.
+pub fn resolve_client(peer: IpAddr, headers: &HeaderMap, trusted: &[IpAddr]) -> IpAddr {
+    if !trusted.contains(&peer) {
+        return peer;
+    }
+
+    let forwarded: Vec<&str> = headers
+        .get_all(FORWARDED_FOR)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    for address in forwarded.into_iter().rev() {
+        match address.parse::<IpAddr>() {
+            Ok(address) if trusted.contains(&address) => continue,
+            Ok(address) => return address,
+            Err(_) => return peer,
+        }
+    }
+
+    peer
+}
+
+/// Extracts the address of the connection a request arrived on, from ClientAddr or warp.
+///
+/// None if it is unknown, like in warp::test requests without remote_addr.
+/// The address is recorded in the request span, so every event of the request carries it.
+pub fn remote() -> impl Filter<Extract = (Option<SocketAddr>,), Error = Infallible> + Clone {
+    warp::ext::optional::<ClientAddr>()
+        .and(warp::addr::remote())
+        .map(|client: Option<ClientAddr>, addr: Option<SocketAddr>| {
+            let addr: Option<SocketAddr> = client.map(|ClientAddr(addr)| addr).or(addr);
+
+            if let Some(addr) = addr {
+                Span::current().record("remote", tracing::field::display(addr.ip()));
+            }
+
+            addr
+        })
+}
+
+/// Extracts the address of the client, see resolve_client.
+///
+/// None if the address of the connection is unknown, see remote.
+pub fn client_ip(
+    trusted: Arc<Vec<IpAddr>>,
+) -> impl Filter<Extract = (Option<IpAddr>,), Error = Infallible> + Clone {
+    remote().and(warp::header::headers_cloned()).map(
+        move |addr: Option<SocketAddr>, headers: HeaderMap| {
+            addr.map(|addr| resolve_client(addr.ip(), &headers, &trusted))
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_for_only_from_trusted_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let trusted = Vec::from([proxy]);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(FORWARDED_FOR, "1.2.3.4, 203.0.113.7".parse().unwrap());
+
+        assert_eq!(resolve_client(proxy, &headers, &trusted), client);
+        assert_eq!(resolve_client(client, &headers, &trusted), client);
+        assert_eq!(resolve_client(proxy, &headers, &[]), proxy);
+
+        headers.insert(FORWARDED_FOR, "203.0.113.7, 10.0.0.1".parse().unwrap());
+        assert_eq!(resolve_client(proxy, &headers, &trusted), client);
+
+        headers.insert(FORWARDED_FOR, "not an address".parse().unwrap());
+        assert_eq!(resolve_client(proxy, &headers, &trusted), proxy);
+
+        assert_eq!(resolve_client(proxy, &HeaderMap::new(), &trusted), proxy);
+    }
+}
@@ -0,0 +1,261 @@
+use std::time::Duration;
+
+use warp::{
+    cors::Builder,
+    filters::BoxedFilter,
+    http::{HeaderName, Method, Uri},
+    reply::Response,
+    Filter, Reply,
+};
+
+use crate::endpoints::rec_path;
+
+/// Response headers browsers are allowed to read.
+const EXPOSED_HEADERS: [&str; 3] = ["x-request-id", "x-vote-applied", "retry-after"];
+
+/// Which browser origins can call the `/rec` routes, and how.
+#[derive(Debug, Clone)]
+pub struct CorsOptions {
+    /// Allowed origins, like `https://example.com`. `*` allows any origin. If empty, CORS is off.
+    pub origins: Vec<String>,
+    pub methods: Vec<Method>,
+    pub headers: Vec<HeaderName>,
+    /// Seconds browsers can cache a preflight response.
+    pub max_age: u64,
+}
+
+impl Default for CorsOptions {
+    fn default() -> Self {
+        CorsOptions {
+            origins: Vec::new(),
+            methods: Vec::from([Method::GET, Method::POST]),
+            headers: ["content-type", "authorization", "x-api-key", "x-request-id"]
+                .into_iter()
+                .map(HeaderName::from_static)
+                .collect(),
+            max_age: 600,
+        }
+    }
+}
+
+/// Returns true if origin can be allowed: `*`, or a scheme and host with an optional port.
+pub fn is_valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+
+    match origin.parse::<Uri>() {
+        Err(_) => false,
+        Ok(uri) => {
+            matches!(uri.scheme_str(), Some("http" | "https"))
+                && uri.authority().is_some()
+                && uri.path_and_query().is_none_or(|path| path == "/")
+                && !origin.ends_with('/')
+        }
+    }
+}
+
+fn builder(options: &CorsOptions) -> Builder {
+    let builder = warp::cors()
+        .allow_methods(options.methods.clone())
+        .allow_headers(options.headers.clone())
+        .expose_headers(EXPOSED_HEADERS)
+        .max_age(Duration::from_secs(options.max_age));
+
+    match options.origins.iter().any(|origin| origin == "*") {
+        true => builder.allow_any_origin(),
+        false => builder.allow_origins(options.origins.iter().map(String::as_str)),
+    }
+}
+
+/// Limit routes to the `/rec` paths, and add the CORS headers in options to their responses.
+///
+/// Preflight OPTIONS requests to `/rec` are answered here. Requests from origins that are not allowed
+/// are rejected with 403. If there are no origins in options, routes are only limited to `/rec`.
+pub fn rec_cors<F, R>(routes: F, options: &CorsOptions) -> BoxedFilter<(Response,)>
+where
+    F: Filter<Extract = (R,), Error = warp::Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + 'static,
+{
+    // The path is checked outside the CORS filter, which answers preflight requests before the routes run.
+    match options.origins.is_empty() {
+        true => rec_path()
+            .and(routes)
+            .map(|reply: R| reply.into_response())
+            .boxed(),
+        false => rec_path()
+            .and(routes.with(builder(options)))
+            .map(Reply::into_response)
+            .boxed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use warp::http::{header, StatusCode};
+
+    use crate::server::recover::recover_all;
+
+    fn routes(options: &CorsOptions) -> BoxedFilter<(Response,)> {
+        let rec = warp::get()
+            .and(warp::path!("rec" / String))
+            .map(|prefix: String| prefix);
+
+        rec_cors(rec, options)
+    }
+
+    fn options() -> CorsOptions {
+        CorsOptions {
+            origins: Vec::from(["https://app.example.com".to_string()]),
+            ..CorsOptions::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn preflight() {
+        let routes = routes(&options());
+
+        let response = warp::test::request()
+            .method("OPTIONS")
+            .path("/rec/am")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "content-type")
+            .reply(&routes)
+            .await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_MAX_AGE], "600");
+
+        let response = warp::test::request()
+            .method("OPTIONS")
+            .path("/rec/am")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = warp::test::request()
+            .method("OPTIONS")
+            .path("/admin/reload")
+            .header(header::ORIGIN, "https://app.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn simple_requests() {
+        let routes = routes(&options());
+
+        let response = warp::test::request()
+            .path("/rec/am")
+            .header(header::ORIGIN, "https://app.example.com")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+
+        let response = warp::test::request()
+            .path("/rec/am")
+            .header(header::ORIGIN, "https://evil.example.com")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Without an Origin header, the request is not a CORS request.
+        let response = warp::test::request().path("/rec/am").reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn errors_have_cors_headers() {
+        let vote = warp::post()
+            .and(warp::path!("rec"))
+            .and(warp::body::content_length_limit(16))
+            .and(warp::body::json())
+            .map(|name: String| name)
+            .recover(recover_all);
+        let routes = rec_cors(vote, &options());
+
+        let request = |body: &str| {
+            warp::test::request()
+                .method("POST")
+                .path("/rec")
+                .header(header::ORIGIN, "https://app.example.com")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body)
+        };
+
+        let response = request(r#""Ann""#).reply(&routes).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        for (body, status) in [
+            (
+                r#""Annabel Annabel Annabel""#,
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+            ("{", StatusCode::BAD_REQUEST),
+        ] {
+            let response = request(body).reply(&routes).await;
+            assert_eq!(response.status(), status);
+            assert_eq!(
+                response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+                "https://app.example.com"
+            );
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        }
+
+        let response = warp::test::request()
+            .method("GET")
+            .path("/rec")
+            .header(header::ORIGIN, "https://app.example.com")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://app.example.com"
+        );
+    }
+
+    #[tokio::test]
+    async fn off_without_origins() {
+        let routes = routes(&CorsOptions::default());
+
+        let response = warp::test::request()
+            .path("/rec/am")
+            .header(header::ORIGIN, "https://app.example.com")
+            .reply(&routes)
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[test]
+    fn origins() {
+        assert!(is_valid_origin("*"));
+        assert!(is_valid_origin("https://app.example.com"));
+        assert!(is_valid_origin("http://localhost:8080"));
+        assert!(!is_valid_origin("https://app.example.com/"));
+        assert!(!is_valid_origin("https://app.example.com/path"));
+        assert!(!is_valid_origin("app.example.com"));
+        assert!(!is_valid_origin("ftp://app.example.com"));
+    }
+}
@@ -1,60 +1,377 @@
-use std::net::SocketAddr;
-use warp::Filter;
+mod access_log;
+mod client;
+mod cors;
+mod recover;
+mod request_id;
+mod tls;
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tokio::{net::TcpListener, sync::watch};
+use tonic::transport::server::TcpIncoming;
+use warp::{http::header, Filter};
 
 use crate::{
-    endpoints::{get_entries, get_top_entries, vote, vote_json},
-    tree::Tree,
+    auth::{require, Auth, Role},
+    endpoints::{
+        accepts_events, dictionary_events, dictionary_export, dictionary_session, dictionary_stats,
+        dictionary_top, events, export, get_dictionary_entries, get_dictionary_top_entries,
+        get_entries, get_top_entries, healthz, limited_vote_json, metadata_json, metrics, readyz,
+        reload, reload_dictionary, session, set_dictionary_metadata, set_metadata, stats, vote,
+        vote_dictionary, when_ready, EventsQuery, ExportQuery, Query,
+    },
+    grpc::{self, GrpcService},
+    limit::RateLimiter,
+    log::{finish, request_span},
+    metrics::track,
+    registry::Registry,
+    reload::{listen_sighup, Reloader},
+    server::{
+        access_log::access,
+        client::remote,
+        cors::rec_cors,
+        recover::recover_all,
+        request_id::{answer, request_id},
+        tls::{listen_sighup_tls, serve_tls},
+    },
 };
 
-/// Set all endpoints and start the server.
+pub use access_log::{AccessLog, AccessLogFormat, AccessLogOptions};
+pub use client::client_ip;
+pub use cors::{is_valid_origin, CorsOptions};
+pub use tls::{Tls, TlsOptions};
+
+/// How the server answers requests, besides the routes themselves.
+pub struct ServerOptions {
+    /// Each request is written to the access log, if there is one.
+    pub access_log: Option<AccessLog>,
+    /// Votes are limited by client address.
+    pub limiter: RateLimiter,
+    /// Proxies whose X-Forwarded-For header is used for the client address.
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Routes need an API key with the role checked by auth, except /healthz and /readyz.
+    pub auth: Auth,
+    /// Browsers can call the /rec routes from the origins in cors.
+    pub cors: CorsOptions,
+    /// Connections are served over TLS if there is a certificate.
+    pub tls: Option<Tls>,
+    /// The gRPC service is served on this address, if set. It doesn't use TLS.
+    pub grpc: Option<SocketAddr>,
+}
+
+/// Set all endpoints and start the server, see ServerOptions.
 ///
 /// Will keep running until the system shuts down.
-pub async fn start(socket_addr: SocketAddr, tree: Tree) {
-    let tree_copy = tree.clone();
+pub async fn start(socket_addr: SocketAddr, registry: Registry, options: ServerOptions) {
+    let ServerOptions {
+        access_log,
+        limiter,
+        trusted_proxies,
+        auth,
+        cors,
+        tls,
+        grpc,
+    } = options;
+    let trusted_proxies: Arc<Vec<IpAddr>> = Arc::new(trusted_proxies);
+    let grpc_auth: Auth = auth.clone();
+    let grpc_limiter: RateLimiter = limiter.clone();
+
+    // Each request gets the Tree that is current when it arrives.
+    // A reload only affects requests that arrive after it finishes.
+    //
+    // Routes without a dictionary name use the default dictionary.
+    let registry_copy = registry.clone();
+    let dictionary_filter = warp::any().map(move || registry_copy.get_default().clone());
+
+    let registry_copy = registry.clone();
+    let reloader_filter = warp::any().map(move || registry_copy.get_default().reloader.clone());
+
+    let registry_copy = registry.clone();
+    let registry_filter = warp::any().map(move || registry_copy.clone());
+
+    // Sessions are matched first, /rec/ws would otherwise be a prefix.
+    // Requests without a WebSocket upgrade fall through to the prefix search.
+    tracing::info!("Endpoint GET WebSocket {}/rec/ws", socket_addr);
+    let rec_session = warp::get()
+        .and(warp::path!("rec" / "ws"))
+        .and(warp::ws())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(dictionary_filter.clone())
+        .and_then(session);
+
+    tracing::info!("Endpoint GET WebSocket {}/rec/[dictionary]/ws", socket_addr);
+    let rec_session = rec_session.or(warp::get()
+        .and(warp::path!("rec" / String / "ws"))
+        .and(warp::ws())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(registry_filter.clone())
+        .and_then(dictionary_session));
 
-    let tree_filter = warp::any().map(move || tree_copy.clone());
+    // Streams of events end when the server shuts down, so they don't hold the shutdown up.
+    let (stop, stopping) = watch::channel(());
+    let grpc_stopping = stopping.clone();
+    let stopping_filter = warp::any().map(move || stopping.clone());
 
-    println!("\n");
-    println!("Endpoint GET {}/rec/[prefix]", socket_addr);
+    // Like sessions, /rec/events would otherwise be a prefix.
+    tracing::info!("Endpoint GET EventStream {}/rec/events", socket_addr);
+    let rec_events = warp::get()
+        .and(warp::path!("rec" / "events"))
+        .and(accepts_events())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<EventsQuery>())
+        .and(dictionary_filter.clone())
+        .and(stopping_filter.clone())
+        .and_then(events);
+
+    tracing::info!(
+        "Endpoint GET EventStream {}/rec/[dictionary]/events",
+        socket_addr
+    );
+    let rec_events = rec_events.or(warp::get()
+        .and(warp::path!("rec" / String / "events"))
+        .and(accepts_events())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<EventsQuery>())
+        .and(registry_filter.clone())
+        .and(stopping_filter)
+        .and_then(dictionary_events));
+
+    tracing::info!("Endpoint GET {}/rec/[dictionary]/[prefix]", socket_addr);
     let get_names = warp::get()
+        .and(warp::path!("rec" / String / String))
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(registry_filter.clone())
+        .and_then(get_dictionary_entries);
+
+    tracing::info!("Endpoint GET {}/rec/[dictionary]/", socket_addr);
+    let get_names = get_names.or(warp::get()
+        .and(dictionary_top(registry.clone()))
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and_then(get_dictionary_top_entries));
+
+    tracing::info!("Endpoint GET {}/rec/[prefix]", socket_addr);
+    let get_names = get_names.or(warp::get()
         .and(warp::path!("rec" / String))
-        .and(tree_filter.clone())
-        .and_then(get_entries);
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(dictionary_filter.clone())
+        .and_then(get_entries));
 
-    println!("Endpoint GET {}/rec", socket_addr);
+    tracing::info!("Endpoint GET {}/rec", socket_addr);
     let get_names = get_names.or(warp::get()
         .and(warp::path!("rec"))
-        .and(tree_filter.clone())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(dictionary_filter.clone())
         .and_then(get_top_entries));
 
-    println!("Endpoint GET {}/rec/", socket_addr);
+    tracing::info!("Endpoint GET {}/rec/", socket_addr);
     let get_names = get_names.or(warp::get()
         .and(warp::path!("rec" / ..))
-        .and(tree_filter.clone())
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::query::<Query>())
+        .and(dictionary_filter.clone())
         .and_then(get_top_entries));
 
-    println!("Endpoint POST Input:JSON {}/rec/", socket_addr);
+    tracing::info!("Endpoint POST Input:JSON {}/rec/", socket_addr);
     let rec_vote = warp::post()
         .and(warp::path("rec"))
         .and(warp::path::end())
-        .and(vote_json())
-        .and(tree_filter)
+        .and(require(auth.clone(), Role::Vote))
+        .and(limited_vote_json(limiter.clone(), trusted_proxies.clone()))
+        .and(dictionary_filter.clone())
         .and_then(vote);
 
-    let routes = get_names.or(rec_vote);
+    tracing::info!("Endpoint POST Input:JSON {}/rec/[dictionary]", socket_addr);
+    let rec_vote = rec_vote.or(warp::post()
+        .and(warp::path!("rec" / String))
+        .and(require(auth.clone(), Role::Vote))
+        .and(limited_vote_json(limiter, trusted_proxies))
+        .and(registry_filter.clone())
+        .and_then(vote_dictionary));
+
+    tracing::info!("Endpoint POST {}/admin/reload", socket_addr);
+    let admin_reload = warp::post()
+        .and(warp::path!("admin" / "reload"))
+        .and(require(auth.clone(), Role::Admin))
+        .and(reloader_filter)
+        .and_then(reload);
+
+    tracing::info!("Endpoint POST {}/admin/reload/[dictionary]", socket_addr);
+    let admin_reload = admin_reload.or(warp::post()
+        .and(warp::path!("admin" / "reload" / String))
+        .and(require(auth.clone(), Role::Admin))
+        .and(registry_filter.clone())
+        .and_then(reload_dictionary));
+
+    tracing::info!("Endpoint PUT Input:JSON {}/admin/metadata", socket_addr);
+    let admin_metadata = warp::put()
+        .and(warp::path!("admin" / "metadata"))
+        .and(require(auth.clone(), Role::Admin))
+        .and(metadata_json())
+        .and(dictionary_filter.clone())
+        .and_then(set_metadata);
 
-    println!("\nStarting server...");
+    tracing::info!(
+        "Endpoint PUT Input:JSON {}/admin/metadata/[dictionary]",
+        socket_addr
+    );
+    let admin_metadata = admin_metadata.or(warp::put()
+        .and(warp::path!("admin" / "metadata" / String))
+        .and(require(auth.clone(), Role::Admin))
+        .and(metadata_json())
+        .and(registry_filter.clone())
+        .and_then(set_dictionary_metadata));
+
+    tracing::info!("Endpoint GET {}/stats", socket_addr);
+    let get_stats = warp::get()
+        .and(warp::path!("stats"))
+        .and(require(auth.clone(), Role::Read))
+        .and(dictionary_filter.clone())
+        .and_then(stats);
+
+    tracing::info!("Endpoint GET {}/stats/[dictionary]", socket_addr);
+    let get_stats = get_stats.or(warp::get()
+        .and(warp::path!("stats" / String))
+        .and(require(auth.clone(), Role::Read))
+        .and(registry_filter.clone())
+        .and_then(dictionary_stats));
+
+    tracing::info!("Endpoint GET {}/export", socket_addr);
+    let get_export = warp::get()
+        .and(warp::path!("export"))
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::header::optional::<String>(header::ACCEPT.as_str()))
+        .and(warp::query::<ExportQuery>())
+        .and(dictionary_filter)
+        .and_then(export);
+
+    tracing::info!("Endpoint GET {}/export/[dictionary]", socket_addr);
+    let get_export = get_export.or(warp::get()
+        .and(warp::path!("export" / String))
+        .and(require(auth.clone(), Role::Read))
+        .and(warp::header::optional::<String>(header::ACCEPT.as_str()))
+        .and(warp::query::<ExportQuery>())
+        .and(registry_filter.clone())
+        .and_then(dictionary_export));
+
+    tracing::info!("Endpoint GET {}/metrics", socket_addr);
+    let get_metrics = warp::get()
+        .and(warp::path!("metrics"))
+        .and(require(auth, Role::Read))
+        .and_then(metrics);
+
+    // Probes don't have API keys.
+    tracing::info!("Endpoint GET {}/healthz", socket_addr);
+    let health = warp::get().and(warp::path!("healthz")).and_then(healthz);
+
+    tracing::info!("Endpoint GET {}/readyz", socket_addr);
+    let health = health.or(warp::get()
+        .and(warp::path!("readyz"))
+        .and(registry_filter)
+        .and_then(readyz));
+
+    // The /rec routes answer 503 until every dictionary is loaded.
+    // Their errors are recovered inside the CORS filter, so browsers can read them too.
+    let rec = rec_cors(
+        when_ready(registry.clone())
+            .and(rec_session.or(rec_events).or(get_names).or(rec_vote))
+            .recover(recover_all),
+        &cors,
+    );
+
+    let routes = rec
+        .or(admin_reload)
+        .or(admin_metadata)
+        .or(get_stats)
+        .or(get_export)
+        .or(get_metrics)
+        .or(health)
+        .recover(recover_all);
+
+    // Every response, errors included, carries the request ID and is written to the access log.
+    let routes = request_id()
+        .and(remote())
+        .and(routes)
+        .map(answer)
+        .with(access(access_log))
+        .with(track())
+        .with(finish())
+        .with(request_span());
+
+    // The gRPC server shares the dictionaries, API keys and vote rate limit, and stops with the HTTP server.
+    let grpc = grpc.map(|grpc_addr| {
+        let incoming = match TcpIncoming::bind(grpc_addr) {
+            Ok(value) => value,
+            Err(err) => {
+                tracing::error!("Failed to bind the gRPC server to {grpc_addr}. {err}");
+                std::process::exit(1);
+            }
+        };
+
+        let service = GrpcService::new(registry.clone(), grpc_auth, grpc_limiter);
+        let mut stopping = grpc_stopping;
+        let shutdown = async move {
+            let _ = stopping.changed().await;
+        };
+
+        tracing::info!("Starting gRPC server on {grpc_addr} .");
+
+        tokio::spawn(async move {
+            if let Err(err) = grpc::serve(service, incoming, shutdown).await {
+                tracing::error!("gRPC server failed. {err}");
+            }
+        })
+    });
+
+    let reloaders: Vec<Reloader> = registry
+        .iter()
+        .map(|(_, dictionary)| dictionary.reloader.clone())
+        .collect();
+    listen_sighup(reloaders);
+
+    match &tls {
+        Some(tls) => {
+            listen_sighup_tls(tls.clone());
+            tracing::info!("Starting server on {socket_addr} with TLS.");
+        }
+        None => tracing::info!("Starting server on {socket_addr} ."),
+    }
 
     // A signal that happens when the user press ctrl+c
     let signal = async move {
         tokio::signal::ctrl_c()
             .await
-            .expect("Failed to listen to shutdown signal.")
+            .expect("Failed to listen to shutdown signal.");
+
+        stop.send_replace(());
     };
 
-    let (_addr, server) = warp::serve(routes).bind_with_graceful_shutdown(socket_addr, signal);
+    match tls {
+        Some(tls) => {
+            let listener = TcpListener::bind(socket_addr)
+                .await
+                .unwrap_or_else(|err| panic!("Failed to bind {socket_addr}. {err}"));
+
+            serve_tls(warp::service(routes), listener, tls, signal).await;
+        }
+        None => {
+            let (_addr, server) =
+                warp::serve(routes).bind_with_graceful_shutdown(socket_addr, signal);
+
+            server.await;
+        }
+    }
 
-    server.await;
+    if let Some(grpc) = grpc {
+        let _ = grpc.await;
+    }
 
-    println!("Shutting down");
+    tracing::info!("Shutting down.");
 }
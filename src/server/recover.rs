@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use warp::{
+    body::BodyDeserializeError,
+    http::{header, HeaderValue, StatusCode},
+    reject::{
+        InvalidHeader, InvalidQuery, LengthRequired, MethodNotAllowed, MissingHeader,
+        PayloadTooLarge, UnsupportedMediaType,
+    },
+    reply::Response,
+    Rejection, Reply,
+};
+
+use crate::{
+    auth::{Forbidden, Unauthorized},
+    endpoints::NotReady,
+    limit::RateLimited,
+};
+
+/// Body of the responses for rejections.
+#[derive(Serialize)]
+struct Error {
+    error: String,
+}
+
+fn json_error(error: String, status: StatusCode) -> Response {
+    let mut response: Response = warp::reply::json(&Error { error }).into_response();
+    *response.status_mut() = status;
+
+    response
+}
+
+/// Turn the custom rejections of the endpoints into JSON responses.
+///
+/// Other rejections are passed on, so warp answers them as usual.
+pub async fn recover(rejection: Rejection) -> Result<Response, Rejection> {
+    let mut retry_after: Option<Duration> = None;
+    let mut authenticate: bool = false;
+
+    let (error, status): (String, StatusCode) = if rejection.find::<NotReady>().is_some() {
+        (
+            "The dictionaries are still loading, try again later".into(),
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+    } else if rejection.find::<Unauthorized>().is_some() {
+        authenticate = true;
+
+        (
+            "Missing or unknown API key".into(),
+            StatusCode::UNAUTHORIZED,
+        )
+    } else if let Some(Forbidden(role)) = rejection.find::<Forbidden>() {
+        (
+            format!("The API key doesn't have the {role} role"),
+            StatusCode::FORBIDDEN,
+        )
+    } else if let Some(RateLimited(wait)) = rejection.find::<RateLimited>() {
+        retry_after = Some(*wait);
+
+        (
+            "Too many votes, try again later".into(),
+            StatusCode::TOO_MANY_REQUESTS,
+        )
+    } else {
+        return Err(rejection);
+    };
+
+    let mut response: Response = json_error(error, status);
+
+    if authenticate {
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    }
+
+    if let Some(wait) = retry_after {
+        // Retry-After is in whole seconds, round up so the retry is allowed.
+        let seconds: u64 = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, seconds.max(1).into());
+    }
+
+    Ok(response)
+}
+
+/// Turn every rejection into a JSON response, the custom ones like recover does.
+///
+/// For routes that add headers to their responses afterwards, like the X-Request-Id header and
+/// the CORS headers of `/rec`, which warp wouldn't add to the responses it makes for rejections.
+pub async fn recover_all(rejection: Rejection) -> Result<Response, Rejection> {
+    let rejection: Rejection = match recover(rejection).await {
+        Ok(response) => return Ok(response),
+        Err(rejection) => rejection,
+    };
+
+    // Most specific first, a request rejected by several routes is answered for the one it got furthest in.
+    let (error, status): (String, StatusCode) =
+        if let Some(err) = rejection.find::<PayloadTooLarge>() {
+            (err.to_string(), StatusCode::PAYLOAD_TOO_LARGE)
+        } else if let Some(err) = rejection.find::<LengthRequired>() {
+            (err.to_string(), StatusCode::LENGTH_REQUIRED)
+        } else if let Some(err) = rejection.find::<UnsupportedMediaType>() {
+            (err.to_string(), StatusCode::UNSUPPORTED_MEDIA_TYPE)
+        } else if let Some(err) = rejection.find::<BodyDeserializeError>() {
+            (err.to_string(), StatusCode::BAD_REQUEST)
+        } else if let Some(err) = rejection.find::<InvalidQuery>() {
+            (err.to_string(), StatusCode::BAD_REQUEST)
+        } else if let Some(err) = rejection.find::<MissingHeader>() {
+            (err.to_string(), StatusCode::BAD_REQUEST)
+        } else if let Some(err) = rejection.find::<InvalidHeader>() {
+            (err.to_string(), StatusCode::BAD_REQUEST)
+        } else if let Some(err) = rejection.find::<MethodNotAllowed>() {
+            (err.to_string(), StatusCode::METHOD_NOT_ALLOWED)
+        } else if rejection.is_not_found() {
+            ("Not found".into(), StatusCode::NOT_FOUND)
+        } else {
+            tracing::error!("Unhandled rejection {rejection:?}");
+
+            (
+                "Internal server error".into(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        };
+
+    Ok(json_error(error, status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{collections::HashMap, net::IpAddr, sync::Arc};
+
+    use warp::Filter;
+
+    use crate::{
+        auth::{require, Auth, AuthOptions, Role},
+        endpoints::{limited_vote_json, rec::post::Input},
+        limit::{RateLimitOptions, RateLimiter},
+    };
+
+    #[tokio::test]
+    async fn rate_limited_votes_are_429() {
+        let limiter = RateLimiter::new(RateLimitOptions {
+            rate: 0.5,
+            burst: 1.0,
+            ..RateLimitOptions::default()
+        });
+        let filter = limited_vote_json(limiter, Arc::new(Vec::new()))
+            .map(|request: Input, _client: Option<IpAddr>| request.name)
+            .recover(recover);
+
+        let vote = |client: &str| {
+            warp::test::request()
+                .method("POST")
+                .path("/rec")
+                .remote_addr(format!("{client}:5000").parse().unwrap())
+                .json(&serde_json::json!({ "name": "Ann" }))
+        };
+
+        let response = vote("203.0.113.7").reply(&filter).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = vote("203.0.113.7").reply(&filter).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "2");
+
+        let response = vote("203.0.113.8").reply(&filter).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn missing_or_weak_keys_are_401_and_403() {
+        let auth = Auth::new(AuthOptions {
+            keys: HashMap::from([("reader".to_string(), Role::Read)]),
+            public_read: false,
+        });
+        let filter = require(auth, Role::Admin).map(|| "done").recover(recover);
+
+        let response = warp::test::request().reply(&filter).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert_eq!(response.body(), r#"{"error":"Missing or unknown API key"}"#);
+
+        let response = warp::test::request()
+            .header("x-api-key", "reader")
+            .reply(&filter)
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            response.body(),
+            r#"{"error":"The API key doesn't have the admin role"}"#
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::{convert::Infallible, net::SocketAddr};
+
+use tracing::Span;
+use uuid::Uuid;
+use warp::{http::HeaderValue, hyper::body::HttpBody, reply::Response, Filter, Reply};
+
+use crate::server::access_log::answered;
+
+/// Header with the ID of a request, read from the request and echoed in the response.
+pub const REQUEST_ID: &str = "x-request-id";
+
+/// Extracts the ID in the X-Request-Id header if it is usable, otherwise a new random ID.
+///
+/// IDs from clients are used as given if they are 1 to 128 visible ASCII characters.
+/// The ID is recorded in the request span, so every event of the request carries it.
+pub fn request_id() -> impl Filter<Extract = (String,), Error = Infallible> + Clone {
+    warp::header::value(REQUEST_ID)
+        .map(Some)
+        .or(warp::any().map(|| None))
+        .unify()
+        .map(|value: Option<HeaderValue>| {
+            let id: String = match value.as_ref().and_then(|value| value.to_str().ok()) {
+                Some(id)
+                    if !id.is_empty()
+                        && id.len() <= 128
+                        && id.bytes().all(|b| b.is_ascii_graphic()) =>
+                {
+                    id.into()
+                }
+                _ => Uuid::new_v4().to_string(),
+            };
+
+            Span::current().record("id", id.as_str());
+
+            id
+        })
+}
+
+/// Echo id in the X-Request-Id header of reply, and pass it on to the access log with remote.
+pub fn answer(id: String, remote: Option<SocketAddr>, reply: impl Reply) -> Response {
+    let mut response: Response = reply.into_response();
+    let header = HeaderValue::from_str(&id).expect("Request IDs are visible ASCII.");
+    response.headers_mut().insert(REQUEST_ID, header);
+
+    answered(id, remote, response.body().size_hint().exact());
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn request_ids() {
+        let filter = request_id();
+
+        let generated: String = warp::test::request().filter(&filter).await.unwrap();
+        assert_eq!(generated.len(), 36);
+
+        let other: String = warp::test::request().filter(&filter).await.unwrap();
+        assert_ne!(generated, other);
+
+        let given: String = warp::test::request()
+            .header(REQUEST_ID, "client-42")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_eq!(given, "client-42");
+
+        let spaces: String = warp::test::request()
+            .header(REQUEST_ID, "has spaces")
+            .filter(&filter)
+            .await
+            .unwrap();
+        assert_ne!(spaces, "has spaces");
+    }
+
+    #[tokio::test]
+    async fn request_id_is_echoed() {
+        let filter = request_id().map(|id: String| answer(id.clone(), None, id));
+
+        let response = warp::test::request()
+            .header(REQUEST_ID, "client-42")
+            .reply(&filter)
+            .await;
+        assert_eq!(response.headers()[REQUEST_ID], "client-42");
+        assert_eq!(response.body(), "client-42");
+
+        let response = warp::test::request().reply(&filter).await;
+        let echoed = response.headers()[REQUEST_ID].to_str().unwrap();
+        assert_eq!(echoed.len(), 36);
+        assert_eq!(response.body(), echoed);
+    }
+}
@@ -0,0 +1,412 @@
+use std::{convert::Infallible, fmt::Display, future::Future, pin::pin, sync::Arc, time::Duration};
+
+use parking_lot::RwLock;
+use tokio::{net::TcpListener, sync::watch};
+use tokio_rustls::{
+    rustls::{
+        crypto::{ring, CryptoProvider},
+        pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer},
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+use warp::{
+    http::Request,
+    hyper::{
+        server::conn::Http,
+        service::{service_fn, Service},
+        Body,
+    },
+    reply::Response,
+};
+
+use crate::server::client::ClientAddr;
+
+/// Time a client has to finish the TLS handshake before its connection is closed.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Paths of the PEM files with the certificate chain and its private key.
+#[derive(Debug, Clone)]
+pub struct TlsOptions {
+    pub cert_path: String,
+    pub key_path: String,
+}
+
+/// Errors loading the certificate or the private key.
+#[derive(Debug)]
+pub enum TlsError {
+    // Path and the reason it couldn't be read
+    Read(String, String),
+    // Path without any certificate
+    NoCertificate(String),
+    // Path without a private key
+    NoKey(String),
+    // The key is not usable, or doesn't match the certificate
+    Invalid(String),
+}
+
+impl Display for TlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            TlsError::Read(path, reason) => write!(f, "Failed to read {}. {}", path, reason),
+            TlsError::NoCertificate(path) => write!(f, "No PEM certificate found in {}", path),
+            TlsError::NoKey(path) => write!(f, "No PEM private key found in {}", path),
+            TlsError::Invalid(reason) => write!(f, "Invalid certificate or key. {}", reason),
+        }
+    }
+}
+
+/// Hands the current certificate to each TLS handshake.
+#[derive(Debug)]
+struct Resolver(RwLock<Arc<CertifiedKey>>);
+
+impl ResolvesServerCert for Resolver {
+    fn resolve(&self, _client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.0.read().clone())
+    }
+}
+
+/// The certificate and key of the server, which can be reloaded while it runs.
+///
+/// Connections that are already open keep the certificate they were accepted with.
+///
+/// Can be cloned. All clones share the same certificate.
+#[derive(Clone)]
+pub struct Tls {
+    options: TlsOptions,
+    provider: Arc<CryptoProvider>,
+    resolver: Arc<Resolver>,
+}
+
+impl Tls {
+    /// Read the certificate and key in options.
+    pub fn load(options: TlsOptions) -> Result<Self, TlsError> {
+        let provider = Arc::new(ring::default_provider());
+        let key: CertifiedKey = read_certified_key(&options, &provider)?;
+
+        Ok(Tls {
+            options,
+            provider,
+            resolver: Arc::new(Resolver(RwLock::new(Arc::new(key)))),
+        })
+    }
+
+    /// Read the certificate and key again, for the connections accepted after this.
+    ///
+    /// If they fail to load, the current certificate is kept.
+    pub fn reload(&self) -> Result<(), TlsError> {
+        let key: CertifiedKey = read_certified_key(&self.options, &self.provider)?;
+        *self.resolver.0.write() = Arc::new(key);
+
+        Ok(())
+    }
+
+    /// Path of the certificate, for logs.
+    pub fn cert_path(&self) -> &str {
+        &self.options.cert_path
+    }
+
+    /// Acceptor for connections, offering HTTP/2 and HTTP/1.1.
+    pub fn acceptor(&self) -> TlsAcceptor {
+        let mut config = ServerConfig::builder_with_provider(self.provider.clone())
+            .with_safe_default_protocol_versions()
+            .expect("The ring provider supports the default TLS versions.")
+            .with_no_client_auth()
+            .with_cert_resolver(self.resolver.clone());
+
+        config.alpn_protocols = Vec::from([b"h2".to_vec(), b"http/1.1".to_vec()]);
+
+        TlsAcceptor::from(Arc::new(config))
+    }
+}
+
+fn read_pem(path: &str) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|err| TlsError::Read(path.into(), err.to_string()))
+}
+
+fn read_certified_key(
+    options: &TlsOptions,
+    provider: &CryptoProvider,
+) -> Result<CertifiedKey, TlsError> {
+    let pem: Vec<u8> = read_pem(&options.cert_path)?;
+    let certs: Vec<CertificateDer<'static>> = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<_, _>>()
+        .map_err(|err| TlsError::Read(options.cert_path.clone(), err.to_string()))?;
+
+    if certs.is_empty() {
+        return Err(TlsError::NoCertificate(options.cert_path.clone()));
+    }
+
+    let pem: Vec<u8> = read_pem(&options.key_path)?;
+    let key = PrivateKeyDer::from_pem_slice(&pem)
+        .map_err(|_| TlsError::NoKey(options.key_path.clone()))?;
+
+    CertifiedKey::from_der(certs, key, provider).map_err(|err| TlsError::Invalid(err.to_string()))
+}
+
+/// Options with the certificate and key paths in dir.
+#[cfg(test)]
+pub fn temp_options(dir: &std::path::Path) -> TlsOptions {
+    TlsOptions {
+        cert_path: dir.join("cert.pem").to_string_lossy().into(),
+        key_path: dir.join("key.pem").to_string_lossy().into(),
+    }
+}
+
+/// Write a new self-signed certificate for localhost and its key to the paths in options.
+#[cfg(test)]
+pub fn self_signed(options: &TlsOptions) -> CertificateDer<'static> {
+    let generated = rcgen::generate_simple_self_signed(["localhost".to_string()]).unwrap();
+
+    std::fs::write(&options.cert_path, generated.cert.pem()).unwrap();
+    std::fs::write(&options.key_path, generated.signing_key.serialize_pem()).unwrap();
+
+    generated.cert.der().clone()
+}
+
+/// Serve service over TLS on the connections of listener until signal completes.
+///
+/// Requests get the address of their connection as the ClientAddr extension.
+/// Connections that don't finish the TLS handshake within HANDSHAKE_TIMEOUT are closed.
+/// Once signal completes no more connections are accepted, and open connections finish
+/// the requests they are answering before closing.
+pub async fn serve_tls<S>(
+    service: S,
+    listener: TcpListener,
+    tls: Tls,
+    signal: impl Future<Output = ()>,
+) where
+    S: Service<Request<Body>, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    let acceptor = tls.acceptor();
+    let (shutdown, watching) = watch::channel(());
+    let mut signal = pin!(signal);
+
+    loop {
+        let (stream, remote) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(value) => value,
+                Err(err) => {
+                    // Usually out of file descriptors, wait for some connections to close.
+                    tracing::error!("Failed to accept a connection. {err}");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+            _ = &mut signal => break,
+        };
+
+        let acceptor = acceptor.clone();
+        let service = service.clone();
+        let mut watching = watching.clone();
+
+        tokio::spawn(async move {
+            let stream = tokio::select! {
+                accepted = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)) => match accepted {
+                    Ok(Ok(value)) => value,
+                    Ok(Err(err)) => {
+                        tracing::debug!(%remote, "TLS handshake failed. {err}");
+                        return;
+                    }
+                    Err(_) => {
+                        tracing::debug!(%remote, "TLS handshake timed out.");
+                        return;
+                    }
+                },
+                _ = watching.changed() => return,
+            };
+
+            let service = service_fn(move |mut request: Request<Body>| {
+                request.extensions_mut().insert(ClientAddr(remote));
+                service.clone().call(request)
+            });
+            let mut connection = pin!(Http::new()
+                .serve_connection(stream, service)
+                .with_upgrades());
+
+            let result = tokio::select! {
+                result = connection.as_mut() => result,
+                _ = watching.changed() => {
+                    connection.as_mut().graceful_shutdown();
+                    connection.await
+                }
+            };
+
+            if let Err(err) = result {
+                tracing::debug!(%remote, "Connection error. {err}");
+            }
+        });
+    }
+
+    // Stop accepting, then wait for every connection to close.
+    drop(listener);
+    drop(watching);
+    shutdown.send_replace(());
+    shutdown.closed().await;
+}
+
+/// Reload the certificate each time the process receives SIGHUP.
+///
+/// The dictionaries are reloaded on the same signal, see listen_sighup.
+#[cfg(unix)]
+pub fn listen_sighup_tls(tls: Tls) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("Failed to listen to SIGHUP.");
+
+    tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+            tracing::info!(
+                source = tls.cert_path(),
+                "SIGHUP received. Reloading the certificate."
+            );
+
+            match tls.reload() {
+                Ok(()) => tracing::info!("Certificate reloaded."),
+                Err(err) => tracing::error!("{err}. Keeping the current certificate."),
+            }
+        }
+    });
+}
+
+/// SIGHUP only exists on unix. The certificate is only read at start.
+#[cfg(not(unix))]
+pub fn listen_sighup_tls(_tls: Tls) {
+    // Doesn't do anything outside of unix.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::net::SocketAddr;
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        sync::oneshot,
+    };
+    use tokio_rustls::{
+        rustls::{ClientConfig, RootCertStore},
+        TlsConnector,
+    };
+    use warp::Filter;
+
+    use crate::server::client::remote;
+
+    #[test]
+    fn failed_reload_keeps_the_certificate() {
+        let dir = tempfile::tempdir().unwrap();
+        let options: TlsOptions = temp_options(dir.path());
+        let first: CertificateDer = self_signed(&options);
+        let tls = Tls::load(options.clone()).unwrap();
+
+        // The key of another certificate.
+        let other_dir = tempfile::tempdir().unwrap();
+        let other: TlsOptions = temp_options(other_dir.path());
+        self_signed(&other);
+        std::fs::copy(&other.key_path, &options.key_path).unwrap();
+
+        assert!(matches!(tls.reload(), Err(TlsError::Invalid(_))));
+        assert_eq!(tls.resolver.0.read().cert[0], first);
+
+        std::fs::write(&options.cert_path, "not a certificate").unwrap();
+        assert!(matches!(tls.reload(), Err(TlsError::NoCertificate(_))));
+
+        let missing = TlsOptions {
+            key_path: "missing-key.pem".into(),
+            ..other
+        };
+        assert!(matches!(Tls::load(missing), Err(TlsError::Read(..))));
+    }
+
+    /// GET path from the server at address, trusting only cert.
+    ///
+    /// Returns the response and the certificate the server presented.
+    async fn get_tls(
+        address: SocketAddr,
+        cert: &CertificateDer<'static>,
+        path: &str,
+    ) -> (String, CertificateDer<'static>) {
+        let mut roots = RootCertStore::empty();
+        roots.add(cert.clone()).unwrap();
+
+        let config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connector = TlsConnector::from(Arc::new(config));
+
+        let stream = TcpStream::connect(address).await.unwrap();
+        let mut stream = connector
+            .connect("localhost".try_into().unwrap(), stream)
+            .await
+            .unwrap();
+
+        let presented = stream.get_ref().1.peer_certificates().unwrap()[0].clone();
+
+        let request =
+            format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        (response, presented)
+    }
+
+    #[tokio::test]
+    async fn tls_with_certificate_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let options: TlsOptions = temp_options(dir.path());
+        let first: CertificateDer = self_signed(&options);
+        let tls = Tls::load(options.clone()).unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address: SocketAddr = listener.local_addr().unwrap();
+
+        let routes = warp::path!("hello")
+            .and(remote())
+            .map(|remote: Option<SocketAddr>| format!("hello {}", remote.unwrap().ip()));
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve_tls(
+            warp::service(routes),
+            listener,
+            tls.clone(),
+            async move {
+                stopped.await.ok();
+            },
+        ));
+
+        let (response, presented) = get_tls(address, &first, "/hello").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("hello 127.0.0.1"));
+        assert_eq!(presented, first);
+
+        // Plain HTTP is not answered.
+        let mut plain = TcpStream::connect(address).await.unwrap();
+        plain
+            .write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        let _ = plain.read_to_string(&mut response).await;
+        assert!(!response.starts_with("HTTP/1.1 200"));
+
+        // New connections get the certificate written after the reload.
+        let second: CertificateDer = self_signed(&options);
+        tls.reload().unwrap();
+
+        let (response, presented) = get_tls(address, &second, "/hello").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert_eq!(presented, second);
+
+        stop.send(()).unwrap();
+        server.await.unwrap();
+        assert!(TcpStream::connect(address).await.is_err());
+    }
+}
@@ -1,11 +1,41 @@
-use std::sync::Arc;
+use std::sync::{atomic::AtomicUsize, Arc};
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 
-use crate::node::Node;
+use crate::{
+    node::Node,
+    tree::{Feed, Votes},
+};
 
 /// Contains Logic related to the Prefix Tree that is used by all other traits.
 pub trait TreeExt {
     /// Returns the atomic pointer for the first Node in the Tree.
     fn get_node(&self) -> &Arc<RwLock<Node>>;
+
+    /// Returns the votes applied to this Tree since it was loaded.
+    fn get_votes(&self) -> &Arc<Mutex<Votes>>;
+
+    /// Returns the number of Nodes and names, kept up to date as entries are included.
+    fn get_size(&self) -> &Size;
+
+    /// Returns the sender of the votes applied to this Tree.
+    fn get_feed(&self) -> &Feed;
+}
+
+/// Number of Nodes and names in a Tree, updated by Load::include.
+///
+/// The starting Node is counted, so an empty Tree has 1 Node.
+#[derive(Debug)]
+pub struct Size {
+    pub nodes: AtomicUsize,
+    pub names: AtomicUsize,
+}
+
+impl Default for Size {
+    fn default() -> Self {
+        Size {
+            nodes: AtomicUsize::new(1),
+            names: AtomicUsize::new(0),
+        }
+    }
 }
@@ -1,22 +1,47 @@
 mod ext;
+#[cfg(test)]
+mod sample;
 
-use std::sync::Arc;
+use std::{
+    collections::HashMap,
+    sync::{atomic::Ordering, Arc, Weak},
+};
 
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::broadcast;
 
 use crate::{
-    node::Node,
+    entry::Entry,
+    node::{Node, NodeExt},
     procedures::{
+        export::tree::Export,
+        find::tree::Find,
         get::tree::Get,
-        load::{tree::Load, LoadError},
+        load::{tree::Load, LoadError, LoadOptions, LoadSummary},
+        metadata::tree::SetMetadata,
+        stats::tree::Stats,
         vote::tree::Vote,
     },
 };
 
-pub use crate::tree::ext::TreeExt;
+pub(crate) use crate::tree::ext::{Size, TreeExt};
+#[cfg(test)]
+pub(crate) use crate::tree::sample::sample_tree;
 
 pub type Counter = usize;
 
+/// Max number of entries returned for a prefix.
+pub type SuggestionNumber = usize;
+
+/// Number of votes each name received since the Tree was loaded.
+pub type Votes = HashMap<String, u64>;
+
+/// Sends the Entry of each vote applied to the Tree, as it is after the vote.
+pub(crate) type Feed = broadcast::Sender<Entry>;
+
+/// Votes a slow subscriber of the Feed can fall behind before it misses some.
+const FEED_CAPACITY: usize = 1024;
+
 /// Prefix Tree for storing values of type Entry on each Node.
 ///
 /// Starts with a Node of name="" and times=0 as the starting point.
@@ -25,16 +50,55 @@ pub type Counter = usize;
 ///
 /// Can be cloned. All clones will point to the same starting Node.
 /// Sharing the same data with each unique thread.
+///
+/// Entries are included with Load, suggested with Get and voted for with Vote:
+///
+/// ```
+/// use typerecommendation::{Get, Load, Tree, Vote, VoteResult};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let tree: Tree = Tree::new_empty(10).await;
+/// tree.include(("Ann", 10).into()).unwrap();
+/// tree.include(("Anna", 30).into()).unwrap();
+///
+/// let shared: Tree = tree.clone();
+/// match shared.vote("ann") {
+///     VoteResult::Success(entry) => assert_eq!(*entry.get_times(), 11),
+///     VoteResult::NotFound => unreachable!(),
+/// }
+///
+/// let top = tree.get_top("an").unwrap();
+/// assert_eq!(top[0].get_name(), "Anna");
+/// assert_eq!(*top[1].get_times(), 11);
+/// # }
+/// ```
 pub struct Tree {
     // The first node
     node: Arc<RwLock<Node>>,
+
+    // Votes applied since loading, so they can be carried over when the Tree is reloaded
+    votes: Arc<Mutex<Votes>>,
+
+    // Counted as entries are included, so measuring the Tree doesn't need a traversal
+    size: Arc<Size>,
+    // Applied votes, shared with the Trees that replace this one on reload
+    feed: Feed,
 }
 
 // Cloning the tree will create another atomic pointer to the same node.
 impl Clone for Tree {
     fn clone(&self) -> Self {
         let node = Arc::clone(&self.node);
-        Self { node }
+        let votes = Arc::clone(&self.votes);
+        let size = Arc::clone(&self.size);
+        let feed = self.feed.clone();
+        Self {
+            node,
+            votes,
+            size,
+            feed,
+        }
     }
 }
 
@@ -42,26 +106,111 @@ impl TreeExt for Tree {
     fn get_node(&self) -> &Arc<RwLock<Node>> {
         &self.node
     }
+
+    fn get_votes(&self) -> &Arc<Mutex<Votes>> {
+        &self.votes
+    }
+
+    fn get_size(&self) -> &Size {
+        &self.size
+    }
+
+    fn get_feed(&self) -> &Feed {
+        &self.feed
+    }
 }
 
+impl Export for Tree {}
+
+impl Find for Tree {}
+
 impl Get for Tree {}
 
 impl Vote for Tree {}
 
 impl Load for Tree {}
 
+impl SetMetadata for Tree {}
+
+impl Stats for Tree {}
+
 impl Tree {
-    /// Creates an instance of Tree and load entries from '/names.json' .
-    pub async fn new(suggestions: usize) -> Result<Self, LoadError> {
+    /// Creates an instance of Tree and load entries from the file in options.path .
+    ///
+    /// Reading the file is blocking, so loading runs on a separate thread to keep the runtime free.
+    pub async fn new(
+        suggestions: usize,
+        options: LoadOptions,
+    ) -> Result<(Self, LoadSummary), LoadError> {
         let tree: Tree = Tree::new_empty(suggestions).await;
-        tree.load()?;
 
-        Ok(tree)
+        let loader: Tree = tree.clone();
+        let summary: LoadSummary = tokio::task::spawn_blocking(move || loader.load(&options))
+            .await
+            .expect("Loading thread panicked.")?;
+
+        Ok((tree, summary))
+    }
+
+    /// The max number of entries returned for a prefix.
+    pub fn get_suggestions(&self) -> usize {
+        self.node.read().get_suggestions()
+    }
+
+    /// Number of names in the Tree, without traversing it.
+    pub fn names(&self) -> Counter {
+        self.size.names.load(Ordering::Relaxed)
+    }
+
+    /// Number of Nodes in the Tree, including the starting Node, without traversing it.
+    pub fn nodes(&self) -> Counter {
+        self.size.nodes.load(Ordering::Relaxed)
     }
 
-    /// Creates an empty instance of Tree. Used for testing.
+    /// Creates an empty instance of Tree.
     pub async fn new_empty(suggestions: usize) -> Self {
         let node: Arc<RwLock<Node>> = Node::new(None, "".into(), 0, suggestions);
-        Tree { node }
+        let votes: Arc<Mutex<Votes>> = Arc::new(Mutex::new(Votes::new()));
+        let size: Arc<Size> = Arc::new(Size::default());
+        let (feed, _) = broadcast::channel(FEED_CAPACITY);
+        Tree {
+            node,
+            votes,
+            size,
+            feed,
+        }
+    }
+
+    /// Send the votes applied to this Tree to the subscribers of tree, instead of its own.
+    ///
+    /// Used when this Tree replaces tree, so its subscribers keep receiving votes.
+    pub fn with_feed_of(mut self, tree: &Tree) -> Self {
+        self.feed = tree.feed.clone();
+        self
+    }
+
+    /// Receive the Entry of each vote applied from now on, to this Tree or the Trees that replace it.
+    pub fn subscribe(&self) -> broadcast::Receiver<Entry> {
+        self.feed.subscribe()
+    }
+
+    /// Identifies this Tree and its clones, without keeping it in memory.
+    pub fn id(&self) -> TreeId {
+        TreeId(Arc::downgrade(&self.node))
+    }
+}
+
+/// Identity of a Tree, see Tree::id.
+///
+/// Equal for clones of the same Tree, and different for Trees created separately,
+/// even after the Tree is dropped.
+#[derive(Clone)]
+pub struct TreeId(Weak<RwLock<Node>>);
+
+impl PartialEq for TreeId {
+    fn eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.0, &other.0)
     }
 }
+
+impl Eq for TreeId {}
@@ -0,0 +1,12 @@
+use crate::{procedures::load::tree::Load, tree::Tree};
+
+/// A Tree with Ann 10, Anna 30, Annabel 20 and Bob 5, the names most tests suggest from.
+pub async fn sample_tree(suggestions: usize) -> Tree {
+    let tree: Tree = Tree::new_empty(suggestions).await;
+
+    for entry in [("Ann", 10), ("Anna", 30), ("Annabel", 20), ("Bob", 5)] {
+        tree.include(entry.into()).unwrap();
+    }
+
+    tree
+}